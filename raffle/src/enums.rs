@@ -6,6 +6,7 @@ use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     CLType, CLTyped, ContractHash, ContractPackageHash, Key,
 };
+use core::convert::TryFrom;
 
 /// An enum representing an [`AccountHash`] or a [`ContractPackageHash`].
 #[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
@@ -79,19 +80,147 @@ impl ToBytes for Address {
     }
 }
 
-impl FromBytes for Address {
-    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (key, remainder) = Key::from_bytes(bytes)?;
+impl TryFrom<Key> for Address {
+    type Error = bytesrepr::Error;
 
-        let address = match key {
-            Key::Account(account_hash) => Address::Account(account_hash),
+    fn try_from(key: Key) -> Result<Self, Self::Error> {
+        match key {
+            Key::Account(account_hash) => Ok(Address::Account(account_hash)),
             Key::Hash(raw_contract_package_hash) => {
                 let contract_package_hash = ContractPackageHash::new(raw_contract_package_hash);
-                Address::Contract(contract_package_hash)
+                Ok(Address::Contract(contract_package_hash))
             }
-            _ => return Err(bytesrepr::Error::Formatting),
-        };
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl FromBytes for Address {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (key, remainder) = Key::from_bytes(bytes)?;
+
+        Ok((Address::try_from(key)?, remainder))
+    }
+}
+
+/// Lifecycle of a raffle, stored under the `status` named key.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Status {
+    /// Deployed, waiting for the prize to be deposited.
+    Created = 0,
+    /// Prize deposited, ticket sales have not started yet.
+    Funded = 1,
+    /// Tickets can be bought.
+    Open = 2,
+    /// Ticket sales are over, waiting for the draw.
+    Closed = 3,
+    /// Winners have been drawn and can claim their prizes.
+    Drawn = 4,
+    /// Every prize has been claimed or returned to the owner.
+    Claimed = 5,
+    /// The raffle was cancelled by the owner.
+    Cancelled = 6,
+    /// The raffle ended without participants and the prizes went back to the owner.
+    NoWinner = 7,
+}
+
+impl TryFrom<u8> for Status {
+    type Error = bytesrepr::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Status::Created),
+            1 => Ok(Status::Funded),
+            2 => Ok(Status::Open),
+            3 => Ok(Status::Closed),
+            4 => Ok(Status::Drawn),
+            5 => Ok(Status::Claimed),
+            6 => Ok(Status::Cancelled),
+            7 => Ok(Status::NoWinner),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl CLTyped for Status {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for Status {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for Status {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (value, remainder) = u8::from_bytes(bytes)?;
+
+        Ok((Status::try_from(value)?, remainder))
+    }
+}
+
+/// Kind of asset a prize is, along with how its contract identifies it.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PrizeKind {
+    /// CEP-47 NFT, identified by a `u64` token id.
+    Cep47 = 0,
+    /// CEP-78 NFT in `Ordinal` identifier mode, passed as `token_id`.
+    Cep78Ordinal = 1,
+    /// CEP-78 NFT in `Hash` identifier mode, passed as `token_hash`.
+    Cep78Hash = 2,
+    /// An amount of a CEP-18 fungible token.
+    Cep18 = 3,
+    /// A fixed amount of motes deposited by the owner.
+    Cspr = 4,
+    /// A share of the ticket sales in basis points, paid out in motes.
+    Pot = 5,
+}
+
+impl TryFrom<u8> for PrizeKind {
+    type Error = bytesrepr::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PrizeKind::Cep47),
+            1 => Ok(PrizeKind::Cep78Ordinal),
+            2 => Ok(PrizeKind::Cep78Hash),
+            3 => Ok(PrizeKind::Cep18),
+            4 => Ok(PrizeKind::Cspr),
+            5 => Ok(PrizeKind::Pot),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl CLTyped for PrizeKind {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for PrizeKind {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for PrizeKind {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (value, remainder) = u8::from_bytes(bytes)?;
 
-        Ok((address, remainder))
+        Ok((PrizeKind::try_from(value)?, remainder))
     }
 }
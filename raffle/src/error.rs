@@ -8,9 +8,25 @@ pub enum Error {
     TimeError = 2,
     WinnerError = 3,
     NotApproved = 4,
-    CancelError = 5,
-    DrawExists = 6,
+    // 5 was `CancelError`, left unused so deployed raffles keep their error codes
+    NoDraw = 6,
     WinnerAlreadyExist = 7,
+    PaymentError = 8,
+    QuantityError = 9,
+    RevealError = 10,
+    ProviderError = 11,
+    StatusError = 12,
+    NoPartipiciants = 13,
+    MinTicketsError = 14,
+    RefundError = 15,
+    MaxTicketsError = 16,
+    WalletLimitError = 17,
+    FeeError = 18,
+    SplitError = 19,
+    WithdrawError = 20,
+    AlreadyClaimed = 21,
+    InvalidPartipiciant = 22,
+    PrizeError = 23,
 }
 
 impl From<Error> for ApiError {
@@ -1,19 +1,57 @@
 use crate::{alloc::string::ToString, utils::get_current_address};
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 use casper_contract::contract_api::storage;
-use casper_types::{Key, URef};
+use casper_types::{Key, URef, U512};
 
 pub enum RaffleEvent {
     BuyTicket {
         partipiciant: Key,
+        quantity: u64,
+        purchase_index: u64,
+        first_ticket: u64,
     },
     Draw {
-        winner: u64,
+        winners: Vec<u64>,
+        owner_amount: U512,
+        fee_amount: U512,
     },
     Claim {
         winner_partipiciant: Key,
         collection: Key,
-        token_id: u64,
+        token_id: String,
+        prize_index: u64,
+    },
+    Reveal {
+        partipiciant: Key,
+        purchase_index: u64,
+    },
+    NoWinner {
+        owner: Key,
+        collection: Key,
+        token_id: String,
+        prize_index: u64,
+    },
+    Refund {
+        account: Key,
+        amount: U512,
+    },
+    Cancel {
+        owner: Key,
+        partipiciant_count: u64,
+    },
+    WithdrawProceeds {
+        account: Key,
+        target: Key,
+        amount: U512,
+    },
+    Forfeit {
+        winner: u64,
+        winner_partipiciant: Key,
+        prize_index: u64,
+    },
+    Redraw {
+        winner: u64,
+        prize_index: u64,
     },
 }
 
@@ -28,23 +66,102 @@ pub fn emit(event: &RaffleEvent) {
             .to_string(),
     );
     match event {
-        RaffleEvent::BuyTicket { partipiciant } => {
+        RaffleEvent::BuyTicket {
+            partipiciant,
+            quantity,
+            purchase_index,
+            first_ticket,
+        } => {
             param.insert("event_type", "buy_ticket".to_string());
             param.insert("partipiciant", partipiciant.to_string());
+            param.insert("quantity", quantity.to_string());
+            param.insert("purchase_index", purchase_index.to_string());
+            param.insert("first_ticket", first_ticket.to_string());
         }
-        RaffleEvent::Draw { winner } => {
+        RaffleEvent::Draw {
+            winners,
+            owner_amount,
+            fee_amount,
+        } => {
+            let winners: Vec<String> = winners.iter().map(|winner| winner.to_string()).collect();
+
             param.insert("event_type", "draw".to_string());
-            param.insert("winner", winner.to_string());
+            param.insert("winners", winners.join(","));
+            param.insert("owner_amount", owner_amount.to_string());
+            param.insert("fee_amount", fee_amount.to_string());
         }
         RaffleEvent::Claim {
             winner_partipiciant,
             collection,
             token_id,
+            prize_index,
         } => {
             param.insert("event_type", "claim".to_string());
             param.insert("winner_partipiciant", winner_partipiciant.to_string());
             param.insert("collection", collection.to_string());
-            param.insert("token_id", token_id.to_string());
+            param.insert("token_id", token_id.clone());
+            param.insert("prize_index", prize_index.to_string());
+        }
+        RaffleEvent::Reveal {
+            partipiciant,
+            purchase_index,
+        } => {
+            param.insert("event_type", "reveal".to_string());
+            param.insert("partipiciant", partipiciant.to_string());
+            param.insert("purchase_index", purchase_index.to_string());
+        }
+        RaffleEvent::NoWinner {
+            owner,
+            collection,
+            token_id,
+            prize_index,
+        } => {
+            param.insert("event_type", "no_winner".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("collection", collection.to_string());
+            param.insert("token_id", token_id.clone());
+            param.insert("prize_index", prize_index.to_string());
+        }
+        RaffleEvent::Refund { account, amount } => {
+            param.insert("event_type", "refund".to_string());
+            param.insert("account", account.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        RaffleEvent::Cancel {
+            owner,
+            partipiciant_count,
+        } => {
+            param.insert("event_type", "cancel".to_string());
+            param.insert("owner", owner.to_string());
+            param.insert("partipiciant_count", partipiciant_count.to_string());
+        }
+        RaffleEvent::WithdrawProceeds {
+            account,
+            target,
+            amount,
+        } => {
+            param.insert("event_type", "withdraw_proceeds".to_string());
+            param.insert("account", account.to_string());
+            param.insert("target", target.to_string());
+            param.insert("amount", amount.to_string());
+        }
+        RaffleEvent::Forfeit {
+            winner,
+            winner_partipiciant,
+            prize_index,
+        } => {
+            param.insert("event_type", "forfeit".to_string());
+            param.insert("winner", winner.to_string());
+            param.insert("winner_partipiciant", winner_partipiciant.to_string());
+            param.insert("prize_index", prize_index.to_string());
+        }
+        RaffleEvent::Redraw {
+            winner,
+            prize_index,
+        } => {
+            param.insert("event_type", "redraw".to_string());
+            param.insert("winner", winner.to_string());
+            param.insert("prize_index", prize_index.to_string());
         }
     }
     events.push(param);
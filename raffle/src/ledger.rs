@@ -0,0 +1,89 @@
+use alloc::{string::ToString, vec::Vec};
+use casper_contract::{contract_api::storage, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, Key, URef,
+};
+
+/// A contiguous block of tickets bought in a single purchase.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TicketRange {
+    /// Index of the first ticket in the block.
+    pub start: u64,
+    /// Number of tickets in the block.
+    pub count: u64,
+    /// Holder of every ticket in the block.
+    pub owner: Key,
+}
+
+impl TicketRange {
+    /// Returns `true` if `ticket_index` falls inside this block.
+    pub fn contains(&self, ticket_index: u64) -> bool {
+        ticket_index >= self.start && ticket_index - self.start < self.count
+    }
+}
+
+impl CLTyped for TicketRange {
+    fn cl_type() -> CLType {
+        <(u64, u64, Key)>::cl_type()
+    }
+}
+
+impl ToBytes for TicketRange {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (self.start, self.count, self.owner).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (self.start, self.count, self.owner).serialized_length()
+    }
+}
+
+impl FromBytes for TicketRange {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let ((start, count, owner), remainder) = <(u64, u64, Key)>::from_bytes(bytes)?;
+
+        Ok((
+            TicketRange {
+                start,
+                count,
+                owner,
+            },
+            remainder,
+        ))
+    }
+}
+
+/// Stores `range` as purchase number `purchase_index` of the ledger.
+pub fn put(ledger: URef, purchase_index: u64, range: TicketRange) {
+    storage::dictionary_put(ledger, &purchase_index.to_string(), range);
+}
+
+/// Reads purchase number `purchase_index` of the ledger.
+pub fn get(ledger: URef, purchase_index: u64) -> Option<TicketRange> {
+    storage::dictionary_get(ledger, &purchase_index.to_string()).unwrap_or_revert()
+}
+
+/// Resolves `ticket_index` to the purchase holding it with a binary search over
+/// the first `purchase_count` purchases, which are ordered by their start index.
+pub fn find(ledger: URef, purchase_count: u64, ticket_index: u64) -> Option<TicketRange> {
+    let mut low: u64 = 0;
+    let mut high: u64 = purchase_count;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let range = get(ledger, mid)?;
+
+        if range.contains(ticket_index) {
+            return Some(range);
+        }
+
+        if ticket_index < range.start {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    None
+}
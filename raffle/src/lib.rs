@@ -5,5 +5,8 @@ extern crate alloc;
 mod enums;
 mod error;
 mod events;
+mod ledger;
+mod payment;
+mod prize;
 mod raffle;
 mod utils;
@@ -0,0 +1,128 @@
+use crate::{
+    error::Error,
+    raffle::PURSE,
+    utils::{self, get_current_package_address},
+};
+use casper_contract::{
+    contract_api::{runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, URef, U256, U512,
+};
+
+/// Contract hash of the CEP-18 token tickets are paid in, `None` for CSPR.
+pub const PAYMENT_TOKEN: &str = "payment_token";
+/// Payment token units held by the raffle. Tracked here since the token does not
+/// expose balances to other contracts, and so that unsolicited transfers are ignored.
+pub const TOKEN_BALANCE: &str = "token_balance";
+
+pub fn payment_token() -> Option<ContractHash> {
+    utils::read_from(PAYMENT_TOKEN)
+}
+
+/// Funds the raffle holds for ticket sales, deposits and proceeds.
+pub fn balance() -> U512 {
+    match payment_token() {
+        Some(_) => utils::read_from(TOKEN_BALANCE),
+        None => system::get_purse_balance(utils::get_uref(PURSE)).unwrap_or_revert(),
+    }
+}
+
+/// Collects exactly `amount` from `payer`, out of `purse` for CSPR raffles or
+/// through the payer's allowance on the payment token otherwise.
+pub fn collect(payer: AccountHash, purse: Option<URef>, amount: U512) {
+    match payment_token() {
+        Some(token) => {
+            // motes sent along to a token raffle would be stranded in the purse
+            if purse.is_some() {
+                runtime::revert(Error::PaymentError);
+            }
+
+            let recipient: Key = get_current_package_address().into();
+
+            runtime::call_contract::<()>(
+                token,
+                "transfer_from",
+                runtime_args! {
+                    "owner" => Key::Account(payer),
+                    "recipient" => recipient,
+                    "amount" => to_u256(amount),
+                },
+            );
+
+            set_token_balance(balance() + amount);
+        }
+        None => {
+            let purse = purse.unwrap_or_revert_with(Error::PaymentError);
+            let purse_balance: U512 = system::get_purse_balance(purse).unwrap_or_revert();
+
+            if purse_balance != amount {
+                runtime::revert(Error::PaymentError);
+            }
+
+            system::transfer_from_purse_to_purse(purse, utils::get_uref(PURSE), amount, None)
+                .unwrap_or_revert_with(Error::PaymentError);
+        }
+    }
+}
+
+/// Pays `amount` out of the raffle's funds to `target`, reverting with `error` if
+/// `target` cannot receive it. CSPR goes to an account or a purse, the payment token
+/// to an account or a contract.
+pub fn pay(target: Key, amount: U512, error: Error) {
+    match payment_token() {
+        Some(token) => {
+            if !matches!(target, Key::Account(_) | Key::Hash(_)) {
+                runtime::revert(error);
+            }
+
+            set_token_balance(balance().checked_sub(amount).unwrap_or_revert_with(error));
+
+            runtime::call_contract::<()>(
+                token,
+                "transfer",
+                runtime_args! {
+                    "recipient" => target,
+                    "amount" => to_u256(amount),
+                },
+            );
+        }
+        None => {
+            let raffle_purse = utils::get_uref(PURSE);
+
+            match target {
+                Key::Account(account_hash) => {
+                    system::transfer_from_purse_to_account(
+                        raffle_purse,
+                        account_hash,
+                        amount,
+                        None,
+                    )
+                    .unwrap_or_revert_with(error);
+                }
+                Key::URef(target_purse) => {
+                    system::transfer_from_purse_to_purse(raffle_purse, target_purse, amount, None)
+                        .unwrap_or_revert_with(error);
+                }
+                _ => runtime::revert(error),
+            }
+        }
+    }
+}
+
+fn set_token_balance(amount: U512) {
+    runtime::put_key(TOKEN_BALANCE, storage::new_uref(amount).into());
+}
+
+/// CEP-18 amounts are `U256`, which covers every amount a raffle can collect.
+fn to_u256(amount: U512) -> U256 {
+    let mut bytes = [0u8; 64];
+    amount.to_little_endian(&mut bytes);
+
+    if bytes[32..].iter().any(|byte| *byte != 0) {
+        runtime::revert(Error::PaymentError);
+    }
+
+    U256::from_little_endian(&bytes[..32])
+}
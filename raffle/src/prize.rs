@@ -0,0 +1,251 @@
+use crate::{
+    enums::PrizeKind,
+    error::Error,
+    raffle::MAX_BPS,
+    utils::{self, get_current_address, get_current_package_address},
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    runtime_args, CLType, CLTyped, ContractHash, Key, RuntimeArgs, U256, U512,
+};
+
+/// Purse holding the motes of CSPR and pot prizes.
+pub const PRIZE_PURSE: &str = "prize_purse";
+
+/// An asset put up as a prize.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Prize {
+    /// Contract hash of the NFT collection or the CEP-18 token, ignored for CSPR.
+    pub collection: Key,
+    /// Kind of asset, which decides how the contract is called.
+    pub kind: PrizeKind,
+    /// Token id in decimal for ordinal NFTs, token hash for hash NFTs, the amount
+    /// in decimal for CEP-18 and CSPR, and the share in basis points for pots.
+    pub token: String,
+}
+
+impl Prize {
+    fn contract_hash(&self) -> ContractHash {
+        self.collection
+            .into_hash()
+            .map(ContractHash::new)
+            .unwrap_or_revert_with(Error::PrizeError)
+    }
+
+    fn token_index(&self) -> u64 {
+        self.token
+            .parse()
+            .map_err(|_| Error::PrizeError)
+            .unwrap_or_revert()
+    }
+
+    fn amount(&self) -> U256 {
+        U256::from_dec_str(&self.token)
+            .map_err(|_| Error::PrizeError)
+            .unwrap_or_revert()
+    }
+
+    /// Returns the motes the owner has to deposit for this prize.
+    pub fn motes(&self) -> U512 {
+        match self.kind {
+            PrizeKind::Cspr => U512::from_dec_str(&self.token)
+                .map_err(|_| Error::PrizeError)
+                .unwrap_or_revert(),
+            _ => U512::zero(),
+        }
+    }
+
+    /// Returns the share of the ticket sales this prize takes.
+    pub fn pot_bps(&self) -> u64 {
+        match self.kind {
+            PrizeKind::Pot => self.token_index(),
+            _ => 0,
+        }
+    }
+
+    /// Returns whether the prize is paid in motes, which only accounts can receive.
+    pub fn pays_motes(&self) -> bool {
+        matches!(self.kind, PrizeKind::Cspr | PrizeKind::Pot)
+    }
+
+    /// Turns a pot into a CSPR prize worth its share of `sales` and returns that
+    /// amount, which the caller moves into the prize purse.
+    pub fn resolve_pot(&mut self, sales: U512) -> U512 {
+        if self.kind != PrizeKind::Pot {
+            return U512::zero();
+        }
+
+        let pot: U512 = sales * U512::from(self.pot_bps()) / U512::from(MAX_BPS);
+
+        self.kind = PrizeKind::Cspr;
+        self.token = pot.to_string();
+
+        pot
+    }
+}
+
+impl CLTyped for Prize {
+    fn cl_type() -> CLType {
+        <(Key, PrizeKind, String)>::cl_type()
+    }
+}
+
+impl ToBytes for Prize {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (self.collection, self.kind, self.token.clone()).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (self.collection, self.kind, self.token.clone()).serialized_length()
+    }
+}
+
+impl FromBytes for Prize {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let ((collection, kind, token), remainder) = <(Key, PrizeKind, String)>::from_bytes(bytes)?;
+
+        let valid = match kind {
+            PrizeKind::Cep47 | PrizeKind::Cep78Ordinal => token.parse::<u64>().is_ok(),
+            PrizeKind::Cep78Hash => true,
+            PrizeKind::Cep18 => U256::from_dec_str(&token).is_ok(),
+            PrizeKind::Cspr => U512::from_dec_str(&token).is_ok(),
+            PrizeKind::Pot => token.parse::<u64>().is_ok(),
+        };
+
+        if !valid {
+            return Err(bytesrepr::Error::Formatting);
+        }
+
+        Ok((
+            Prize {
+                collection,
+                kind,
+                token,
+            },
+            remainder,
+        ))
+    }
+}
+
+/// Pulls `prize` from `owner` into the raffle, reverting unless the raffle was
+/// approved for it first. CSPR arrives through the prize purse instead and pots are
+/// only funded at the draw.
+pub fn deposit(prize: &Prize, owner: Key) {
+    match prize.kind {
+        PrizeKind::Cep18 => {
+            // CEP-18 tracks balances and allowances under the caller's package
+            let spender: Key = get_current_package_address().into();
+            let allowance = runtime::call_contract::<U256>(
+                prize.contract_hash(),
+                "allowance",
+                runtime_args! {
+                    "owner" => owner,
+                    "spender" => spender,
+                },
+            );
+
+            if allowance < prize.amount() {
+                runtime::revert(Error::NotApproved);
+            }
+
+            runtime::call_contract::<()>(
+                prize.contract_hash(),
+                "transfer_from",
+                runtime_args! {
+                    "owner" => owner,
+                    "recipient" => spender,
+                    "amount" => prize.amount(),
+                },
+            );
+        }
+        PrizeKind::Cspr | PrizeKind::Pot => {}
+        _ => {
+            get_approved(prize, owner).unwrap_or_revert_with(Error::NotApproved);
+            transfer_nft(prize, owner, get_current_address().into());
+        }
+    }
+}
+
+/// Sends `prize` held by the raffle to `target`.
+pub fn transfer(prize: &Prize, target: Key) {
+    match prize.kind {
+        PrizeKind::Cep18 => runtime::call_contract::<()>(
+            prize.contract_hash(),
+            "transfer",
+            runtime_args! {
+                "recipient" => target,
+                "amount" => prize.amount(),
+            },
+        ),
+        PrizeKind::Cspr => transfer_motes(prize.motes(), target),
+        // an unresolved pot was never funded
+        PrizeKind::Pot => {}
+        _ => transfer_nft(prize, get_current_address().into(), target),
+    }
+}
+
+fn transfer_motes(amount: U512, target: Key) {
+    if amount.is_zero() {
+        return;
+    }
+
+    // motes can only be paid out to an account's main purse
+    let account_hash = target
+        .into_account()
+        .unwrap_or_revert_with(Error::PrizeError);
+    let prize_purse = utils::get_uref(PRIZE_PURSE);
+
+    system::transfer_from_purse_to_account(prize_purse, account_hash, amount, None)
+        .unwrap_or_revert();
+}
+
+fn get_approved(prize: &Prize, owner: Key) -> Option<Key> {
+    let args = match prize.kind {
+        PrizeKind::Cep47 => runtime_args! {
+            "owner" => owner,
+            "token_id" => prize.token_index(),
+        },
+        PrizeKind::Cep78Ordinal => runtime_args! {
+            "token_id" => prize.token_index(),
+        },
+        PrizeKind::Cep78Hash => runtime_args! {
+            "token_hash" => prize.token.clone(),
+        },
+        _ => runtime::revert(Error::PrizeError),
+    };
+
+    runtime::call_contract::<Option<Key>>(prize.contract_hash(), "get_approved", args)
+}
+
+fn transfer_nft(prize: &Prize, source: Key, target: Key) {
+    let args = match prize.kind {
+        PrizeKind::Cep47 | PrizeKind::Cep78Ordinal => runtime_args! {
+            "token_id" => prize.token_index(),
+            "source_key" => source,
+            "target_key" => target,
+        },
+        PrizeKind::Cep78Hash => runtime_args! {
+            "token_hash" => prize.token.clone(),
+            "source_key" => source,
+            "target_key" => target,
+        },
+        _ => runtime::revert(Error::PrizeError),
+    };
+
+    // CEP-78 answers with the receipt name and the owned tokens dictionary key,
+    // which the raffle has no use for
+    match prize.kind {
+        PrizeKind::Cep47 => runtime::call_contract::<()>(prize.contract_hash(), "transfer", args),
+        _ => {
+            runtime::call_contract::<(String, Key)>(prize.contract_hash(), "transfer", args);
+        }
+    }
+}
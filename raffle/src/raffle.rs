@@ -1,21 +1,26 @@
-use core::ops::Add;
+use core::{convert::TryFrom, ops::Add};
 
 use alloc::{
+    boxed::Box,
     string::{String, ToString},
     vec,
+    vec::Vec,
 };
 
 use crate::{
-    enums::Address,
+    enums::{Address, Status},
     error::Error,
     events::{emit, RaffleEvent},
-    utils::{self, get_current_address, get_key},
+    ledger::{self, TicketRange},
+    payment::{self, PAYMENT_TOKEN, TOKEN_BALANCE},
+    prize::{self, Prize, PRIZE_PURSE},
+    utils::{self, get_key},
 };
 
 use casper_types::{
-    account::AccountHash, contracts::NamedKeys, runtime_args, CLType, CLValue, ContractHash,
-    EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, URef,
-    U512,
+    account::AccountHash, bytesrepr::ToBytes, contracts::NamedKeys, runtime_args,
+    system::CallStackElement, CLType, CLTyped, CLValue, ContractHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, URef, U512,
 };
 
 use casper_contract::contract_api::{runtime, storage, system};
@@ -27,207 +32,643 @@ use tiny_keccak::{Hasher, Sha3};
 const NAME: &str = "name";
 const START_DATE: &str = "start_date";
 const END_DATE: &str = "end_date";
-const COLLECTION: &str = "collection";
-const NFT_INDEX: &str = "nft_index";
+const PRIZES: &str = "prizes";
+const PRIZE_INDEX: &str = "prize_index";
 const PRICE: &str = "price";
+const BUNDLES: &str = "bundles";
 const OWNER: &str = "owner";
-const PURSE: &str = "purse";
+pub(crate) const PURSE: &str = "purse";
 const PARTIPICANT_COUNT: &str = "partipiciant_count";
 const PARTIPICANT_DICT: &str = "partipiciant_dict";
+const PURCHASE_COUNT: &str = "purchase_count";
 const PARTIPICANT: &str = "partipiciant";
+const QUANTITY: &str = "quantity";
 const WINNER: &str = "winner";
 const STORAGE_KEY: &str = "storage_key";
 const CLAIMED: &str = "claimed";
+const SECRET: &str = "secret";
+const SECRET_HASH: &str = "secret_hash";
+const PURCHASE_HASH: &str = "purchase_hash";
+const COMMITMENT: &str = "commitment";
+const COMMITMENT_DICT: &str = "commitment_dict";
+const REVEAL_HASH: &str = "reveal_hash";
+const REVEAL_DEPOSIT: &str = "reveal_deposit";
+const REVEAL_DURATION: &str = "reveal_duration";
+const PURCHASE_INDEX: &str = "purchase_index";
+const RANDOMNESS_PROVIDER: &str = "randomness_provider";
+const REQUEST_ID: &str = "request_id";
+const RANDOM_WORD: &str = "random_word";
+const CALLBACK_CONTRACT: &str = "callback_contract";
+const CALLBACK_ENTRY_POINT: &str = "callback_entry_point";
+const STATUS: &str = "status";
+const MIN_TICKETS: &str = "min_tickets";
+const PAYMENT_DICT: &str = "payment_dict";
+const MAX_TICKETS: &str = "max_tickets";
+const MAX_TICKETS_PER_WALLET: &str = "max_tickets_per_wallet";
+const WALLET_TICKETS_DICT: &str = "wallet_tickets_dict";
+const FEE_BPS: &str = "fee_bps";
+const FEE_RECIPIENT: &str = "fee_recipient";
+const REVENUE_SPLIT: &str = "revenue_split";
+const PROCEEDS_DICT: &str = "proceeds_dict";
+const TARGET: &str = "target";
+const CLAIM_PERIOD: &str = "claim_period";
+const CLAIM_DEADLINE: &str = "claim_deadline";
+const DRAW_PERIOD: &str = "draw_period";
+const DRAW_DEADLINE: &str = "draw_deadline";
+const SEED: &str = "seed";
+const FORFEITED: &str = "forfeited";
+const RECLAIM: &str = "reclaim";
+
+pub(crate) const MAX_BPS: u64 = 10_000;
+// keeps the table `quote_tickets` builds under 100 * 100 entries
+const MAX_BUNDLE_QUANTITY: u64 = 100;
 
 //entry points
 const ENTRY_POINT_DRAW: &str = "draw";
 const ENTRY_POINT_CLAIM: &str = "claim";
+const ENTRY_POINT_DELIVER: &str = "deliver";
 const ENTRY_POINT_DEPOSIT: &str = "deposit";
-const ENTRY_POINT_GET_PRICE: &str = "get_price";
-const ENTRY_POINT_GET_PURSE: &str = "get_purse";
+const ENTRY_POINT_QUOTE: &str = "quote";
 const ENTRY_POINT_BUY_TICKET: &str = "buy_ticket";
 const ENTRY_POINT_CANCEL: &str = "cancel";
+const ENTRY_POINT_REVEAL: &str = "reveal";
+const ENTRY_POINT_REQUEST_DRAW: &str = "request_draw";
+const ENTRY_POINT_FULFILL_DRAW: &str = "fulfill_draw";
+const ENTRY_POINT_GET_STATUS: &str = "get_status";
+const ENTRY_POINT_REFUND: &str = "refund";
+const ENTRY_POINT_GET_REVENUE_SPLIT: &str = "get_revenue_split";
+const ENTRY_POINT_WITHDRAW_PROCEEDS: &str = "withdraw_proceeds";
+const ENTRY_POINT_REDRAW: &str = "redraw";
+
+// randomness provider entry points
+const PROVIDER_ENTRY_POINT_REQUEST_RANDOM: &str = "request_random";
+
+// storage contract entry points
+const STORAGE_ENTRY_POINT_GET_FEE: &str = "get_fee";
 
 #[no_mangle]
 pub extern "C" fn cancel() {
     check_admin_account();
 
     let now: u64 = runtime::get_blocktime().into();
-    let end_date: u64 = utils::read_from(END_DATE);
 
-    if end_date.lt(&now) {
-        runtime::revert(Error::TimeError);
+    // ticket holders get their payments back through `refund` once cancelled
+    match current_status(now) {
+        Status::Funded | Status::Open => {}
+        Status::Closed if is_below_min_tickets() || is_past_draw_deadline(now) => {}
+        Status::Closed => runtime::revert(Error::TimeError),
+        _ => runtime::revert(Error::StatusError),
     }
 
-    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+    let prizes: Vec<Prize> = utils::read_from(PRIZES);
+    let caller: AccountHash = runtime::get_caller();
 
-    if partipiciant_count > 0 {
-        runtime::revert(Error::CancelError);
+    for prize in prizes.iter() {
+        prize::transfer(prize, caller.into());
     }
 
-    let collection: Key = utils::read_from(COLLECTION);
-    let token_id: u64 = utils::read_from(NFT_INDEX);
-    let collection_hash: ContractHash = collection.into_hash().map(ContractHash::new).unwrap();
-    let caller: AccountHash = runtime::get_caller();
-    let contract_address = get_current_address();
-
-    transfer(
-        collection_hash,
-        contract_address.into(),
-        caller.into(),
-        token_id,
-    );
-    runtime::put_key(CLAIMED, storage::new_uref(true).into());
+    runtime::put_key(CLAIMED, storage::new_uref(vec![true; prizes.len()]).into());
     runtime::put_key(END_DATE, storage::new_uref(now).into());
+    set_status(Status::Cancelled);
+
+    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+
+    emit(
+        &(RaffleEvent::Cancel {
+            owner: caller.into(),
+            partipiciant_count,
+        }),
+    )
 }
 
 #[no_mangle]
 pub extern "C" fn draw() {
     check_admin_account();
 
+    let randomness_provider: Option<ContractHash> = utils::read_from(RANDOMNESS_PROVIDER);
+
+    if randomness_provider.is_some() {
+        runtime::revert(Error::ProviderError);
+    }
+
     let now: u64 = runtime::get_blocktime().into();
-    let end_date: u64 = utils::read_from(END_DATE);
+    check_drawable(now);
 
-    if end_date.gt(&now) {
-        runtime::revert(Error::TimeError);
+    // nothing to protect with the secret when nobody entered
+    if !has_partipiciants() {
+        finish_empty_draw();
+        return;
+    }
+
+    if is_below_min_tickets() {
+        runtime::revert(Error::MinTicketsError);
+    }
+
+    // the owner committed to the secret before any ticket was sold, but without
+    // participant reveals they can still predict the draw, see the README
+    let secret: [u8; 32] = runtime::get_named_arg(SECRET);
+    let secret_hash: [u8; 32] = utils::read_from(SECRET_HASH);
+
+    if sha3_256(&[&secret]) != secret_hash {
+        runtime::revert(Error::RevealError);
+    }
+
+    let purchase_hash: [u8; 32] = utils::read_from(PURCHASE_HASH);
+    let reveal_hash: [u8; 32] = utils::read_from(REVEAL_HASH);
+
+    // the block time is left out on purpose, the owner picks it by picking when to draw
+    let hash_bytes = sha3_256(&[&secret, &purchase_hash, &reveal_hash]);
+
+    // kept so anyone can recompute the draw from the published inputs
+    runtime::put_key(SECRET, storage::new_uref(secret).into());
+
+    finish_draw(hash_bytes);
+}
+
+/// Asks the configured randomness provider for a random word, see the README for the
+/// entry points a provider contract has to expose.
+#[no_mangle]
+pub extern "C" fn request_draw() {
+    check_admin_account();
+
+    let randomness_provider: Option<ContractHash> = utils::read_from(RANDOMNESS_PROVIDER);
+    let provider_hash = randomness_provider.unwrap_or_revert_with(Error::ProviderError);
+
+    let now: u64 = runtime::get_blocktime().into();
+    check_drawable(now);
+
+    if !has_partipiciants() {
+        finish_empty_draw();
+        return;
+    }
+
+    if is_below_min_tickets() {
+        runtime::revert(Error::MinTicketsError);
     }
 
-    let winner: Option<u64> = utils::read_from(WINNER);
-    match winner {
-        Some(_value) => runtime::revert(Error::WinnerAlreadyExist),
-        None => {}
+    // a new request supersedes one the provider never answered
+    let request_id: u64 = if runtime::has_key(REQUEST_ID) {
+        utils::read_from::<u64>(REQUEST_ID).add(1u64)
+    } else {
+        0u64
     };
 
+    runtime::put_key(REQUEST_ID, storage::new_uref(request_id).into());
+
+    runtime::call_contract::<()>(
+        provider_hash,
+        PROVIDER_ENTRY_POINT_REQUEST_RANDOM,
+        runtime_args! {
+            REQUEST_ID => request_id,
+            CALLBACK_CONTRACT => utils::get_current_contract_hash(),
+            CALLBACK_ENTRY_POINT => ENTRY_POINT_FULFILL_DRAW.to_string(),
+        },
+    );
+}
+
+/// Callback for the randomness provider, completing the draw started by `request_draw`.
+#[no_mangle]
+pub extern "C" fn fulfill_draw() {
+    let randomness_provider: Option<ContractHash> = utils::read_from(RANDOMNESS_PROVIDER);
+    let provider_hash = randomness_provider.unwrap_or_revert_with(Error::ProviderError);
+
+    match utils::get_immediate_caller() {
+        Some(CallStackElement::StoredContract { contract_hash, .. })
+            if contract_hash == provider_hash => {}
+        _ => runtime::revert(Error::ProviderError),
+    }
+
+    let now: u64 = runtime::get_blocktime().into();
+    check_drawable(now);
+
+    let request_id: u64 = runtime::get_named_arg(REQUEST_ID);
+    let random_word: [u8; 32] = runtime::get_named_arg(RANDOM_WORD);
+
+    if !runtime::has_key(REQUEST_ID) || utils::read_from::<u64>(REQUEST_ID) != request_id {
+        runtime::revert(Error::ProviderError);
+    }
+
+    let purchase_hash: [u8; 32] = utils::read_from(PURCHASE_HASH);
+    let reveal_hash: [u8; 32] = utils::read_from(REVEAL_HASH);
+
+    let hash_bytes = sha3_256(&[&random_word, &purchase_hash, &reveal_hash]);
+
+    // kept so anyone can recompute the draw from the published inputs
+    runtime::put_key(RANDOM_WORD, storage::new_uref(random_word).into());
+
+    finish_draw(hash_bytes);
+}
+
+#[no_mangle]
+pub extern "C" fn buy_ticket() {
+    let now: u64 = runtime::get_blocktime().into();
+
+    match current_status(now) {
+        Status::Open => {}
+        Status::Funded | Status::Closed => runtime::revert(Error::TimeError),
+        _ => runtime::revert(Error::StatusError),
+    }
+
+    set_status(Status::Open);
+
+    let partipiciant: Key = runtime::get_named_arg(PARTIPICANT);
+    let quantity: u64 = runtime::get_named_arg(QUANTITY);
+
+    // only accounts and contracts can ever receive the prize
+    let partipiciant_address: Address =
+        Address::try_from(partipiciant).unwrap_or_revert_with(Error::InvalidPartipiciant);
+
+    // a contract could win a CSPR prize it has no way to be paid
+    if !matches!(partipiciant, Key::Account(_)) {
+        let prizes: Vec<Prize> = utils::read_from(PRIZES);
+
+        if prizes.iter().any(Prize::pays_motes) {
+            runtime::revert(Error::InvalidPartipiciant);
+        }
+    }
+
+    if quantity == 0 {
+        runtime::revert(Error::QuantityError);
+    }
+
+    // a limit of zero means unlimited
     let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+    let max_tickets: u64 = utils::read_from(MAX_TICKETS);
 
-    let input = now.to_string();
-    let mut sha3 = Sha3::v256();
+    if max_tickets > 0 && partipiciant_count.saturating_add(quantity) > max_tickets {
+        runtime::revert(Error::MaxTicketsError);
+    }
 
-    sha3.update(input.as_ref());
+    let wallet_tickets_dict = utils::get_uref(WALLET_TICKETS_DICT);
+    let wallet: String = wallet_key(partipiciant_address);
+    let wallet_tickets: u64 = storage::dictionary_get(wallet_tickets_dict, &wallet)
+        .unwrap_or_revert()
+        .unwrap_or_default();
+    let max_tickets_per_wallet: u64 = utils::read_from(MAX_TICKETS_PER_WALLET);
+
+    if max_tickets_per_wallet > 0
+        && wallet_tickets.saturating_add(quantity) > max_tickets_per_wallet
+    {
+        runtime::revert(Error::WalletLimitError);
+    }
 
-    let mut hash_bytes = [0u8; 32]; // SHA-3-256 for 32 byte
-    sha3.finalize(&mut hash_bytes);
+    storage::dictionary_put(wallet_tickets_dict, &wallet, wallet_tickets.add(quantity));
+
+    // CSPR buyers fund a purse of their own for this call only, so the payment
+    // cannot be credited to anybody else's purchase; token buyers pass `None`
+    let buyer_purse: Option<URef> = runtime::get_named_arg(PURSE);
+    let commitment: Option<[u8; 32]> = runtime::get_named_arg(COMMITMENT);
+    let mut cost: U512 = quote_tickets(quantity);
+
+    // committing buyers lock a deposit that is only returned once they reveal
+    if commitment.is_some() {
+        let reveal_duration: u64 = utils::read_from(REVEAL_DURATION);
+
+        // without a reveal window the deposit could never be won back
+        if reveal_duration == 0 {
+            runtime::revert(Error::RevealError);
+        }
+
+        let reveal_deposit: U512 = utils::read_from(REVEAL_DEPOSIT);
+        cost = cost
+            .checked_add(reveal_deposit)
+            .unwrap_or_revert_with(Error::PaymentError);
+    }
 
-    let hash_number = bytes_to_u64(&hash_bytes);
+    // refunds go back to whoever paid, not to the ticket holder
+    let payer: AccountHash = runtime::get_caller();
 
-    let random_winner = hash_number % partipiciant_count;
+    payment::collect(payer, buyer_purse, cost);
 
-    runtime::put_key(WINNER, storage::new_uref(random_winner).into());
+    let payment_dict = utils::get_uref(PAYMENT_DICT);
+    let paid: U512 = storage::dictionary_get(payment_dict, &payer.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_default();
 
-    let key = runtime::get_key(PURSE).unwrap_or_revert();
-    let contract_purse: URef = key.into_uref().unwrap_or_revert();
+    storage::dictionary_put(payment_dict, &payer.to_string(), paid.add(cost));
 
-    // let owner = runtime::get_caller();
-    let owner = utils::read_from(OWNER);
-    let balance: U512 = system::get_purse_balance(contract_purse).unwrap_or_revert();
+    let purchase_count: u64 = utils::read_from(PURCHASE_COUNT);
 
-    system::transfer_from_purse_to_account(contract_purse, owner, balance, None).unwrap();
+    let partipiciant_dict = utils::get_uref(PARTIPICANT_DICT);
+
+    ledger::put(
+        partipiciant_dict,
+        purchase_count,
+        TicketRange {
+            start: partipiciant_count,
+            count: quantity,
+            owner: partipiciant,
+        },
+    );
+
+    runtime::put_key(
+        PARTIPICANT_COUNT,
+        storage::new_uref(partipiciant_count.add(quantity)).into(),
+    );
+    runtime::put_key(
+        PURCHASE_COUNT,
+        storage::new_uref(purchase_count.add(1u64)).into(),
+    );
+
+    // a sold out raffle ends right away so it can be drawn without waiting for `END_DATE`
+    if max_tickets > 0 && partipiciant_count.add(quantity) == max_tickets {
+        runtime::put_key(END_DATE, storage::new_uref(now).into());
+        set_status(Status::Closed);
+    }
+
+    if let Some(commitment) = commitment {
+        let commitment_dict = utils::get_uref(COMMITMENT_DICT);
+        storage::dictionary_put(
+            commitment_dict,
+            &purchase_count.to_string(),
+            (commitment, payer, false),
+        );
+    }
+
+    // chain every purchase into the entropy used by `draw`
+    let purchase_hash: [u8; 32] = utils::read_from(PURCHASE_HASH);
+    let purchase_hash = sha3_256(&[
+        &purchase_hash,
+        &partipiciant.to_bytes().unwrap_or_revert(),
+        &quantity.to_be_bytes(),
+        &now.to_be_bytes(),
+    ]);
+
+    runtime::put_key(PURCHASE_HASH, storage::new_uref(purchase_hash).into());
 
     emit(
-        &(RaffleEvent::Draw {
-            winner: random_winner,
+        &(RaffleEvent::BuyTicket {
+            partipiciant,
+            quantity,
+            purchase_index: purchase_count,
+            first_ticket: partipiciant_count,
         }),
     )
 }
 
 #[no_mangle]
-pub extern "C" fn buy_ticket() {
+pub extern "C" fn reveal() {
     let now: u64 = runtime::get_blocktime().into();
+    check_closed(now);
+
     let end_date: u64 = utils::read_from(END_DATE);
+    let reveal_duration: u64 = utils::read_from(REVEAL_DURATION);
 
-    if now.gt(&end_date) {
+    if !end_date.add(reveal_duration).gt(&now) {
         runtime::revert(Error::TimeError);
     }
 
-    let start_date: u64 = utils::read_from(START_DATE);
+    let purchase_index: u64 = runtime::get_named_arg(PURCHASE_INDEX);
+    let secret: [u8; 32] = runtime::get_named_arg(SECRET);
+    let caller: AccountHash = runtime::get_caller();
+
+    let partipiciant_dict = utils::get_uref(PARTIPICANT_DICT);
+    let purchase =
+        ledger::get(partipiciant_dict, purchase_index).unwrap_or_revert_with(Error::RevealError);
 
-    if start_date.gt(&now) {
-        runtime::revert(Error::TimeError);
+    // the deposit belongs to whoever paid for the purchase
+    let commitment_dict = utils::get_uref(COMMITMENT_DICT);
+    let (commitment, payer, revealed): ([u8; 32], AccountHash, bool) =
+        storage::dictionary_get(commitment_dict, &purchase_index.to_string())
+            .unwrap_or_revert()
+            .unwrap_or_revert_with(Error::RevealError);
+
+    if payer != caller || revealed || sha3_256(&[&secret]) != commitment {
+        runtime::revert(Error::RevealError);
     }
 
-    let partipiciant: Key = runtime::get_named_arg(PARTIPICANT);
+    storage::dictionary_put(
+        commitment_dict,
+        &purchase_index.to_string(),
+        (commitment, payer, true),
+    );
 
-    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+    let reveal_hash: [u8; 32] = utils::read_from(REVEAL_HASH);
+    let reveal_hash = sha3_256(&[&reveal_hash, &secret]);
 
-    let partipiciant_dict = *runtime::get_key(PARTIPICANT_DICT)
-        .unwrap()
-        .as_uref()
-        .unwrap();
+    runtime::put_key(REVEAL_HASH, storage::new_uref(reveal_hash).into());
+
+    // deposits of buyers who never reveal stay in the purse and count as sales on `draw`,
+    // split between the fee recipient and the revenue split through `withdraw_proceeds`
+    let reveal_deposit: U512 = utils::read_from(REVEAL_DEPOSIT);
+    payment::pay(caller.into(), reveal_deposit, Error::PaymentError);
+
+    let payment_dict = utils::get_uref(PAYMENT_DICT);
+    let paid: U512 = storage::dictionary_get(payment_dict, &caller.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_default();
 
     storage::dictionary_put(
-        partipiciant_dict,
-        &partipiciant_count.to_string(),
-        partipiciant,
+        payment_dict,
+        &caller.to_string(),
+        paid.saturating_sub(reveal_deposit),
     );
 
-    runtime::put_key(
-        PARTIPICANT_COUNT,
-        storage::new_uref(partipiciant_count.add(1u64)).into(),
-    );
+    emit(
+        &(RaffleEvent::Reveal {
+            partipiciant: purchase.owner,
+            purchase_index,
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn refund() {
+    let now: u64 = runtime::get_blocktime().into();
+
+    // an owner who never draws cannot keep the ticket payments locked
+    match current_status(now) {
+        Status::Cancelled => {}
+        Status::Closed if is_below_min_tickets() || is_past_draw_deadline(now) => {}
+        _ => runtime::revert(Error::RefundError),
+    }
 
-    emit(&(RaffleEvent::BuyTicket { partipiciant }))
+    let caller: AccountHash = runtime::get_caller();
+    let payment_dict = utils::get_uref(PAYMENT_DICT);
+    let paid: U512 = storage::dictionary_get(payment_dict, &caller.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_default();
+
+    if paid.is_zero() {
+        runtime::revert(Error::RefundError);
+    }
+
+    storage::dictionary_put(payment_dict, &caller.to_string(), U512::zero());
+
+    payment::pay(caller.into(), paid, Error::RefundError);
+
+    emit(
+        &(RaffleEvent::Refund {
+            account: caller.into(),
+            amount: paid,
+        }),
+    )
 }
 
+/// Pays out the caller's accrued share of the proceeds to `target`, which is an account
+/// or, for CSPR raffles, a purse and, for token raffles, a contract.
 #[no_mangle]
-pub extern "C" fn get_price() {
-    let price: U512 = utils::read_from(PRICE);
+pub extern "C" fn withdraw_proceeds() {
+    let target: Key = runtime::get_named_arg(TARGET);
+    let caller: AccountHash = runtime::get_caller();
+
+    let proceeds_dict = utils::get_uref(PROCEEDS_DICT);
+    let proceeds: U512 = storage::dictionary_get(proceeds_dict, &caller.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_default();
+
+    if proceeds.is_zero() {
+        runtime::revert(Error::WithdrawError);
+    }
 
-    runtime::ret(CLValue::from_t(price).unwrap_or_revert());
+    storage::dictionary_put(proceeds_dict, &caller.to_string(), U512::zero());
+
+    payment::pay(target, proceeds, Error::WithdrawError);
+
+    emit(
+        &(RaffleEvent::WithdrawProceeds {
+            account: caller.into(),
+            target,
+            amount: proceeds,
+        }),
+    )
 }
 
+/// Returns what `quantity` tickets cost with the bundles applied, without any reveal deposit.
 #[no_mangle]
-pub extern "C" fn get_purse() {
-    let raffle_purse = match runtime::get_key(PURSE) {
-        Some(purse_key) => purse_key.into_uref().unwrap_or_revert(),
-        None => {
-            let new_purse = system::create_purse();
-            runtime::put_key(PURSE, new_purse.into());
-            new_purse
-        }
-    };
+pub extern "C" fn quote() {
+    let quantity: u64 = runtime::get_named_arg(QUANTITY);
+
+    runtime::ret(CLValue::from_t(quote_tickets(quantity)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_revenue_split() {
+    let revenue_split: Vec<(AccountHash, u64)> = utils::read_from(REVENUE_SPLIT);
+
+    runtime::ret(CLValue::from_t(revenue_split).unwrap_or_revert());
+}
 
-    runtime::ret(CLValue::from_t(raffle_purse.into_add()).unwrap_or_revert());
+#[no_mangle]
+pub extern "C" fn get_status() {
+    let now: u64 = runtime::get_blocktime().into();
+    let status = current_status(now);
+
+    runtime::ret(CLValue::from_t(status).unwrap_or_revert());
 }
 
 #[no_mangle]
 pub extern "C" fn claim() {
+    let prize_index: u64 = runtime::get_named_arg(PRIZE_INDEX);
+    let winner_partipiciant: Address = get_claimable_winner(prize_index);
     let caller: AccountHash = runtime::get_caller();
-    let winner: u64 = utils::read_from(WINNER);
 
-    let partipiciant_dict = *runtime::get_key(PARTIPICANT_DICT)
-        .unwrap()
-        .as_uref()
-        .unwrap();
+    if winner_partipiciant != Address::Account(caller) {
+        runtime::revert(Error::WinnerError);
+    }
 
-    let winner_partipiciant: Key = storage::dictionary_get(partipiciant_dict, &winner.to_string())
-        .unwrap()
-        .unwrap_or_revert_with(Error::WinnerError);
+    deliver_prize(prize_index, winner_partipiciant);
+}
 
-    if winner_partipiciant != Key::Account(caller) {
-        runtime::revert(Error::WinnerError);
+/// Sends a prize to its recorded winner, which lets anyone complete the claim for
+/// winners that cannot call `claim` themselves, such as contracts.
+#[no_mangle]
+pub extern "C" fn deliver() {
+    let prize_index: u64 = runtime::get_named_arg(PRIZE_INDEX);
+    let winner_partipiciant: Address = get_claimable_winner(prize_index);
+
+    deliver_prize(prize_index, winner_partipiciant);
+}
+
+/// Forfeits a prize whose winner missed their claim deadline, then either draws a new
+/// winner among the remaining tickets or, with `reclaim`, returns the NFT to the owner.
+#[no_mangle]
+pub extern "C" fn redraw() {
+    check_admin_account();
+
+    let now: u64 = runtime::get_blocktime().into();
+
+    if current_status(now) != Status::Drawn {
+        runtime::revert(Error::StatusError);
     }
 
-    let contract_address = get_current_address();
-    let token_id: u64 = utils::read_from(NFT_INDEX);
-    let collection: Key = utils::read_from(COLLECTION);
-    let collection_hash: ContractHash = collection.into_hash().map(ContractHash::new).unwrap();
+    let prize_index: u64 = runtime::get_named_arg(PRIZE_INDEX);
+    let reclaim: bool = runtime::get_named_arg(RECLAIM);
+    let index = prize_index as usize;
 
-    transfer(
-        collection_hash,
-        contract_address.into(),
-        winner_partipiciant,
-        token_id,
-    );
+    let claimed: Vec<bool> = utils::read_from(CLAIMED);
+
+    if *claimed.get(index).unwrap_or_revert_with(Error::PrizeError) {
+        runtime::revert(Error::AlreadyClaimed);
+    }
+
+    let claim_period: u64 = utils::read_from(CLAIM_PERIOD);
+    let mut claim_deadlines: Vec<u64> = utils::read_from(CLAIM_DEADLINE);
 
-    runtime::put_key(CLAIMED, storage::new_uref(true).into());
+    // a zero claim period means the winner can take their time
+    if claim_period == 0 || !now.gt(&claim_deadlines[index]) {
+        runtime::revert(Error::TimeError);
+    }
+
+    let mut winners: Vec<u64> = utils::read_from(WINNER);
+    let winner = winners[index];
 
     emit(
-        &(RaffleEvent::Claim {
-            winner_partipiciant,
-            collection,
-            token_id,
+        &(RaffleEvent::Forfeit {
+            winner,
+            winner_partipiciant: get_ticket_owner(winner),
+            prize_index,
         }),
     );
+
+    let mut forfeited: Vec<u64> = utils::read_from(FORFEITED);
+    forfeited.push(winner);
+    runtime::put_key(FORFEITED, storage::new_uref(forfeited.clone()).into());
+
+    // every redraw forfeits one ticket, so this counts the redraws so far
+    let redraw_count = forfeited.len() as u64;
+
+    // neither forfeited tickets nor the winners of the other prizes can win again
+    let mut excluded: Vec<u64> = forfeited;
+    for (other_index, other_winner) in winners.iter().enumerate() {
+        if other_index != index {
+            excluded.push(*other_winner);
+        }
+    }
+
+    // a forfeited ticket stays the winner of a reclaimed prize, so it can be listed twice
+    excluded.sort_unstable();
+    excluded.dedup();
+
+    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+
+    if reclaim || excluded.len() as u64 >= partipiciant_count {
+        return_prize_to_owner(prize_index);
+        return;
+    }
+
+    let seed: [u8; 32] = utils::read_from(SEED);
+    // the owner picks when to redraw, so the block time stays out of the seed
+    let seed = sha3_256(&[
+        &seed,
+        &prize_index.to_be_bytes(),
+        &redraw_count.to_be_bytes(),
+    ]);
+    let random_winner = pick_ticket(&seed, partipiciant_count, &excluded);
+
+    winners[index] = random_winner;
+    claim_deadlines[index] = now.add(claim_period);
+
+    runtime::put_key(WINNER, storage::new_uref(winners).into());
+    runtime::put_key(SEED, storage::new_uref(seed).into());
+    runtime::put_key(CLAIM_DEADLINE, storage::new_uref(claim_deadlines).into());
+
+    emit(
+        &(RaffleEvent::Redraw {
+            winner: random_winner,
+            prize_index,
+        }),
+    )
 }
 
 // admin function
@@ -235,26 +676,56 @@ pub extern "C" fn claim() {
 pub extern "C" fn deposit() {
     check_admin_account();
 
-    let contract_address = get_current_address();
+    let now: u64 = runtime::get_blocktime().into();
+
+    if current_status(now) != Status::Created {
+        runtime::revert(Error::StatusError);
+    }
+
     let caller: AccountHash = runtime::get_caller();
-    let token_id: u64 = utils::read_from(NFT_INDEX);
-    let collection: Key = utils::read_from(COLLECTION);
-    let collection_hash: ContractHash = collection.into_hash().map(ContractHash::new).unwrap();
+    let prizes: Vec<Prize> = utils::read_from(PRIZES);
 
-    get_approved(collection_hash, caller.into(), token_id)
-        .unwrap_or_revert_with(Error::NotApproved);
+    for prize in prizes.iter() {
+        prize::deposit(prize, caller.into());
+    }
 
-    // check owner is caller
-    transfer(
-        collection_hash,
-        caller.into(),
-        contract_address.into(),
-        token_id,
-    );
+    // CSPR prizes are paid in through a purse prepared by the deposit session
+    let prize_purse = system::create_purse();
+    let prize_motes: U512 = prizes
+        .iter()
+        .map(|prize| prize.motes())
+        .fold(U512::zero(), U512::add);
+
+    if !prize_motes.is_zero() {
+        let purse: Option<URef> = runtime::get_named_arg(PURSE);
+        let purse = purse.unwrap_or_revert_with(Error::PaymentError);
+
+        if system::get_purse_balance(purse).unwrap_or_revert() != prize_motes {
+            runtime::revert(Error::PaymentError);
+        }
+
+        system::transfer_from_purse_to_purse(purse, prize_purse, prize_motes, None)
+            .unwrap_or_revert();
+    }
+
+    runtime::put_key(PRIZE_PURSE, prize_purse.into());
 
     storage::new_dictionary(PARTIPICANT_DICT).unwrap_or_default();
 
     runtime::put_key(PARTIPICANT_COUNT, storage::new_uref(0u64).into());
+    runtime::put_key(PURCHASE_COUNT, storage::new_uref(0u64).into());
+    runtime::put_key(PURCHASE_HASH, storage::new_uref([0u8; 32]).into());
+    runtime::put_key(REVEAL_HASH, storage::new_uref([0u8; 32]).into());
+
+    storage::new_dictionary(COMMITMENT_DICT).unwrap_or_default();
+    storage::new_dictionary(PAYMENT_DICT).unwrap_or_default();
+    storage::new_dictionary(WALLET_TICKETS_DICT).unwrap_or_default();
+    storage::new_dictionary(PROCEEDS_DICT).unwrap_or_default();
+
+    let raffle_purse = system::create_purse();
+    runtime::put_key(PURSE, raffle_purse.into());
+
+    set_status(Status::Funded);
 }
 
 #[no_mangle]
@@ -262,31 +733,166 @@ pub extern "C" fn call() {
     let name: String = runtime::get_named_arg(NAME);
     let start_date: u64 = runtime::get_named_arg(START_DATE);
     let end_date: u64 = runtime::get_named_arg(END_DATE);
-    let nft_index: u64 = runtime::get_named_arg(NFT_INDEX);
     let price: U512 = runtime::get_named_arg(PRICE);
-    let collection: Key = runtime::get_named_arg(COLLECTION);
+    let mut bundles: Vec<(u64, U512)> = runtime::get_named_arg(BUNDLES);
+    let prizes: Vec<Prize> = runtime::get_named_arg(PRIZES);
+    let payment_token: Option<ContractHash> = runtime::get_named_arg(PAYMENT_TOKEN);
     let storage_key: ContractHash = runtime::get_named_arg(STORAGE_KEY);
+    let secret_hash: [u8; 32] = runtime::get_named_arg(SECRET_HASH);
+    let reveal_deposit: U512 = runtime::get_named_arg(REVEAL_DEPOSIT);
+    let reveal_duration: u64 = runtime::get_named_arg(REVEAL_DURATION);
+    let randomness_provider: Option<ContractHash> = runtime::get_named_arg(RANDOMNESS_PROVIDER);
+    let min_tickets: u64 = runtime::get_named_arg(MIN_TICKETS);
+    let max_tickets: u64 = runtime::get_named_arg(MAX_TICKETS);
+    let max_tickets_per_wallet: u64 = runtime::get_named_arg(MAX_TICKETS_PER_WALLET);
+    let mut revenue_split: Vec<(AccountHash, u64)> = runtime::get_named_arg(REVENUE_SPLIT);
+    let claim_period: u64 = runtime::get_named_arg(CLAIM_PERIOD);
+    let draw_period: u64 = runtime::get_named_arg(DRAW_PERIOD);
     //utils
     let owner: AccountHash = runtime::get_caller();
     let now: u64 = runtime::get_blocktime().into();
 
+    let pot_bps: u64 = prizes
+        .iter()
+        .try_fold(0u64, |total, prize| total.checked_add(prize.pot_bps()))
+        .unwrap_or_revert_with(Error::PrizeError);
+
+    // pots are paid in CSPR, so they can only come out of CSPR ticket sales
+    if prizes.is_empty() || pot_bps > MAX_BPS || (pot_bps > 0 && payment_token.is_some()) {
+        runtime::revert(Error::PrizeError);
+    }
+
+    // the draw has to happen within `draw_period` of the reveal window closing
+    let draw_deadline: u64 = end_date
+        .checked_add(reveal_duration)
+        .and_then(|reveal_end| reveal_end.checked_add(draw_period))
+        .unwrap_or_revert_with(Error::TimeError);
+
+    if draw_period == 0 {
+        runtime::revert(Error::TimeError);
+    }
+
+    // a raffle capped below its minimum could never be drawn
+    if max_tickets > 0 && min_tickets > max_tickets {
+        runtime::revert(Error::MinTicketsError);
+    }
+
+    // the marketplace sets its own fee, the raffle creator has no say in it
+    let (fee_bps, fee_recipient): (u64, AccountHash) =
+        runtime::call_contract(storage_key, STORAGE_ENTRY_POINT_GET_FEE, runtime_args! {});
+
+    if fee_bps > MAX_BPS {
+        runtime::revert(Error::FeeError);
+    }
+
+    // shares are in basis points of what is left after the fee, defaulting to the owner
+    if revenue_split.is_empty() {
+        revenue_split.push((owner, MAX_BPS));
+    }
+
+    let total_bps = revenue_split
+        .iter()
+        .try_fold(0u64, |total, (_, share_bps)| total.checked_add(*share_bps));
+
+    if total_bps != Some(MAX_BPS) {
+        runtime::revert(Error::SplitError);
+    }
+
+    // largest bundles first, `quote_tickets` relies on it
+    bundles.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+
+    // a bundle has to hold several tickets and cost less than buying them one by one
+    let invalid_bundle = bundles.iter().any(|(quantity, bundle_price)| {
+        *quantity < 2
+            || *quantity > MAX_BUNDLE_QUANTITY
+            || price
+                .checked_mul(U512::from(*quantity))
+                .map_or(false, |full_price| *bundle_price >= full_price)
+    });
+    let duplicate_bundle = bundles.windows(2).any(|pair| pair[0].0 == pair[1].0);
+
+    if invalid_bundle || duplicate_bundle {
+        runtime::revert(Error::QuantityError);
+    }
+
     let mut named_keys = NamedKeys::new();
 
     named_keys.insert(NAME.to_string(), storage::new_uref(name.clone()).into());
     named_keys.insert(START_DATE.to_string(), storage::new_uref(start_date).into());
     named_keys.insert(END_DATE.to_string(), storage::new_uref(end_date).into());
     named_keys.insert(PRICE.to_string(), storage::new_uref(price).into());
+    named_keys.insert(BUNDLES.to_string(), storage::new_uref(bundles).into());
     named_keys.insert(OWNER.to_string(), storage::new_uref(owner).into());
-    named_keys.insert(COLLECTION.to_string(), storage::new_uref(collection).into());
-    named_keys.insert(NFT_INDEX.to_string(), storage::new_uref(nft_index).into());
+    named_keys.insert(
+        CLAIMED.to_string(),
+        storage::new_uref(vec![false; prizes.len()]).into(),
+    );
+    named_keys.insert(PRIZES.to_string(), storage::new_uref(prizes).into());
+    named_keys.insert(
+        PAYMENT_TOKEN.to_string(),
+        storage::new_uref(payment_token).into(),
+    );
+    named_keys.insert(
+        TOKEN_BALANCE.to_string(),
+        storage::new_uref(U512::zero()).into(),
+    );
     named_keys.insert(
         STORAGE_KEY.to_string(),
         storage::new_uref(storage_key).into(),
     );
+    named_keys.insert(
+        SECRET_HASH.to_string(),
+        storage::new_uref(secret_hash).into(),
+    );
+    named_keys.insert(
+        REVEAL_DEPOSIT.to_string(),
+        storage::new_uref(reveal_deposit).into(),
+    );
+    named_keys.insert(
+        REVEAL_DURATION.to_string(),
+        storage::new_uref(reveal_duration).into(),
+    );
+    named_keys.insert(
+        RANDOMNESS_PROVIDER.to_string(),
+        storage::new_uref(randomness_provider).into(),
+    );
+    named_keys.insert(
+        STATUS.to_string(),
+        storage::new_uref(Status::Created).into(),
+    );
+    named_keys.insert(
+        MIN_TICKETS.to_string(),
+        storage::new_uref(min_tickets).into(),
+    );
+    named_keys.insert(
+        MAX_TICKETS.to_string(),
+        storage::new_uref(max_tickets).into(),
+    );
+    named_keys.insert(
+        MAX_TICKETS_PER_WALLET.to_string(),
+        storage::new_uref(max_tickets_per_wallet).into(),
+    );
+    named_keys.insert(FEE_BPS.to_string(), storage::new_uref(fee_bps).into());
+    named_keys.insert(
+        FEE_RECIPIENT.to_string(),
+        storage::new_uref(fee_recipient).into(),
+    );
+    named_keys.insert(
+        REVENUE_SPLIT.to_string(),
+        storage::new_uref(revenue_split).into(),
+    );
+    named_keys.insert(
+        CLAIM_PERIOD.to_string(),
+        storage::new_uref(claim_period).into(),
+    );
+    named_keys.insert(
+        DRAW_DEADLINE.to_string(),
+        storage::new_uref(draw_deadline).into(),
+    );
 
     let draw_entry_point = EntryPoint::new(
         ENTRY_POINT_DRAW,
-        vec![],
+        vec![Parameter::new(SECRET, CLType::ByteArray(32))],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -294,7 +900,15 @@ pub extern "C" fn call() {
 
     let claim_entry_point = EntryPoint::new(
         ENTRY_POINT_CLAIM,
-        vec![],
+        vec![Parameter::new(PRIZE_INDEX, CLType::U64)],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let deliver_entry_point = EntryPoint::new(
+        ENTRY_POINT_DELIVER,
+        vec![Parameter::new(PRIZE_INDEX, CLType::U64)],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -302,52 +916,133 @@ pub extern "C" fn call() {
 
     let deposit_entry_point = EntryPoint::new(
         ENTRY_POINT_DEPOSIT,
-        vec![],
+        vec![Parameter::new(
+            PURSE,
+            CLType::Option(Box::new(CLType::URef)),
+        )],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let get_price_entry_point = EntryPoint::new(
-        ENTRY_POINT_GET_PRICE,
-        vec![],
+    let quote_entry_point = EntryPoint::new(
+        ENTRY_POINT_QUOTE,
+        vec![Parameter::new(QUANTITY, CLType::U64)],
         CLType::U512,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let get_purse_entry_point = EntryPoint::new(
-        ENTRY_POINT_GET_PURSE,
+    let buy_ticket_entry_point = EntryPoint::new(
+        ENTRY_POINT_BUY_TICKET,
+        vec![
+            Parameter::new(PARTIPICANT, CLType::Key),
+            Parameter::new(QUANTITY, CLType::U64),
+            Parameter::new(PURSE, CLType::Option(Box::new(CLType::URef))),
+            Parameter::new(COMMITMENT, CLType::Option(Box::new(CLType::ByteArray(32)))),
+        ],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let cancel_entry_point = EntryPoint::new(
+        ENTRY_POINT_CANCEL,
         vec![],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let buy_ticket_entry_point = EntryPoint::new(
-        ENTRY_POINT_BUY_TICKET,
-        vec![Parameter::new(PARTIPICANT, CLType::Key)],
+    let reveal_entry_point = EntryPoint::new(
+        ENTRY_POINT_REVEAL,
+        vec![
+            Parameter::new(PURCHASE_INDEX, CLType::U64),
+            Parameter::new(SECRET, CLType::ByteArray(32)),
+        ],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let cancel_entry_point = EntryPoint::new(
-        ENTRY_POINT_CANCEL,
+    let request_draw_entry_point = EntryPoint::new(
+        ENTRY_POINT_REQUEST_DRAW,
         vec![],
         CLType::URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
+    let fulfill_draw_entry_point = EntryPoint::new(
+        ENTRY_POINT_FULFILL_DRAW,
+        vec![
+            Parameter::new(REQUEST_ID, CLType::U64),
+            Parameter::new(RANDOM_WORD, CLType::ByteArray(32)),
+        ],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let get_status_entry_point = EntryPoint::new(
+        ENTRY_POINT_GET_STATUS,
+        vec![],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let refund_entry_point = EntryPoint::new(
+        ENTRY_POINT_REFUND,
+        vec![],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let get_revenue_split_entry_point = EntryPoint::new(
+        ENTRY_POINT_GET_REVENUE_SPLIT,
+        vec![],
+        Vec::<(AccountHash, u64)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let withdraw_proceeds_entry_point = EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_PROCEEDS,
+        vec![Parameter::new(TARGET, CLType::Key)],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let redraw_entry_point = EntryPoint::new(
+        ENTRY_POINT_REDRAW,
+        vec![
+            Parameter::new(PRIZE_INDEX, CLType::U64),
+            Parameter::new(RECLAIM, CLType::Bool),
+        ],
+        CLType::URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
     let mut entry_points = EntryPoints::new();
     entry_points.add_entry_point(draw_entry_point);
     entry_points.add_entry_point(claim_entry_point);
+    entry_points.add_entry_point(deliver_entry_point);
     entry_points.add_entry_point(deposit_entry_point);
-    entry_points.add_entry_point(get_price_entry_point);
-    entry_points.add_entry_point(get_purse_entry_point);
+    entry_points.add_entry_point(quote_entry_point);
     entry_points.add_entry_point(buy_ticket_entry_point);
     entry_points.add_entry_point(cancel_entry_point);
+    entry_points.add_entry_point(reveal_entry_point);
+    entry_points.add_entry_point(request_draw_entry_point);
+    entry_points.add_entry_point(fulfill_draw_entry_point);
+    entry_points.add_entry_point(get_status_entry_point);
+    entry_points.add_entry_point(refund_entry_point);
+    entry_points.add_entry_point(get_revenue_split_entry_point);
+    entry_points.add_entry_point(withdraw_proceeds_entry_point);
+    entry_points.add_entry_point(redraw_entry_point);
 
     let str1 = name.clone() + "_" + &now.to_string();
 
@@ -376,6 +1071,369 @@ pub extern "C" fn call() {
     );
 }
 
+fn get_ticket_owner(ticket_index: u64) -> Key {
+    let partipiciant_dict = utils::get_uref(PARTIPICANT_DICT);
+    let purchase_count: u64 = utils::read_from(PURCHASE_COUNT);
+
+    ledger::find(partipiciant_dict, purchase_count, ticket_index)
+        .unwrap_or_revert_with(Error::WinnerError)
+        .owner
+}
+
+/// Dictionary item key for a wallet, the bare hex of its hash. Formatted keys carry a
+/// prefix that takes them past the 64 bytes dictionary item keys are limited to.
+fn wallet_key(partipiciant: Address) -> String {
+    match partipiciant {
+        Address::Account(account_hash) => account_hash.to_string(),
+        Address::Contract(contract_package_hash) => contract_package_hash.to_string(),
+    }
+}
+
+/// Reads the stored status, moving a funded raffle along with its sale window.
+fn current_status(now: u64) -> Status {
+    let status: Status = utils::read_from(STATUS);
+
+    match status {
+        Status::Funded | Status::Open => {
+            let start_date: u64 = utils::read_from(START_DATE);
+            let end_date: u64 = utils::read_from(END_DATE);
+
+            if now.gt(&end_date) {
+                Status::Closed
+            } else if start_date.gt(&now) {
+                Status::Funded
+            } else {
+                Status::Open
+            }
+        }
+        _ => status,
+    }
+}
+
+fn set_status(status: Status) {
+    runtime::put_key(STATUS, storage::new_uref(status).into());
+}
+
+fn check_closed(now: u64) {
+    match current_status(now) {
+        Status::Closed => set_status(Status::Closed),
+        Status::Funded | Status::Open => runtime::revert(Error::TimeError),
+        Status::Drawn | Status::Claimed | Status::NoWinner => {
+            runtime::revert(Error::WinnerAlreadyExist)
+        }
+        _ => runtime::revert(Error::StatusError),
+    }
+}
+
+fn check_drawable(now: u64) {
+    check_closed(now);
+
+    let end_date: u64 = utils::read_from(END_DATE);
+    let reveal_duration: u64 = utils::read_from(REVEAL_DURATION);
+
+    // participants get the whole reveal window before their entropy is consumed
+    if end_date.add(reveal_duration).gt(&now) || is_past_draw_deadline(now) {
+        runtime::revert(Error::TimeError);
+    }
+}
+
+/// Past the draw deadline the raffle can no longer be drawn, only refunded and cancelled.
+fn is_past_draw_deadline(now: u64) -> bool {
+    let draw_deadline: u64 = utils::read_from(DRAW_DEADLINE);
+    now.gt(&draw_deadline)
+}
+
+fn get_claimable_winner(prize_index: u64) -> Address {
+    let now: u64 = runtime::get_blocktime().into();
+
+    match current_status(now) {
+        Status::Drawn => {}
+        Status::Claimed => runtime::revert(Error::AlreadyClaimed),
+        Status::NoWinner => runtime::revert(Error::NoPartipiciants),
+        Status::Funded | Status::Open | Status::Closed => runtime::revert(Error::NoDraw),
+        _ => runtime::revert(Error::StatusError),
+    }
+
+    let claimed: Vec<bool> = utils::read_from(CLAIMED);
+
+    if *claimed
+        .get(prize_index as usize)
+        .unwrap_or_revert_with(Error::PrizeError)
+    {
+        runtime::revert(Error::AlreadyClaimed);
+    }
+
+    let winners: Vec<u64> = utils::read_from(WINNER);
+    let winner: u64 = *winners
+        .get(prize_index as usize)
+        .unwrap_or_revert_with(Error::PrizeError);
+
+    Address::try_from(get_ticket_owner(winner)).unwrap_or_revert_with(Error::WinnerError)
+}
+
+fn deliver_prize(prize_index: u64, winner: Address) {
+    let winner_partipiciant: Key = winner.into();
+    let prizes: Vec<Prize> = utils::read_from(PRIZES);
+    let prize = &prizes[prize_index as usize];
+
+    prize::transfer(prize, winner_partipiciant);
+    settle_prize(prize_index);
+
+    emit(
+        &(RaffleEvent::Claim {
+            winner_partipiciant,
+            collection: prize.collection,
+            token_id: prize.token.clone(),
+            prize_index,
+        }),
+    );
+}
+
+/// Marks a prize as handed out, closing the raffle once every prize is.
+fn settle_prize(prize_index: u64) {
+    let mut claimed: Vec<bool> = utils::read_from(CLAIMED);
+    claimed[prize_index as usize] = true;
+
+    let all_claimed = claimed.iter().all(|prize_claimed| *prize_claimed);
+
+    runtime::put_key(CLAIMED, storage::new_uref(claimed).into());
+
+    if all_claimed {
+        set_status(Status::Claimed);
+    }
+}
+
+fn has_partipiciants() -> bool {
+    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+    partipiciant_count > 0
+}
+
+fn is_below_min_tickets() -> bool {
+    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+    let min_tickets: u64 = utils::read_from(MIN_TICKETS);
+    partipiciant_count < min_tickets
+}
+
+/// Hands a prize nobody can win back to the owner.
+fn return_prize_to_owner(prize_index: u64) {
+    let owner: AccountHash = utils::read_from(OWNER);
+    let prizes: Vec<Prize> = utils::read_from(PRIZES);
+    let prize = &prizes[prize_index as usize];
+
+    prize::transfer(prize, owner.into());
+    settle_prize(prize_index);
+
+    emit(
+        &(RaffleEvent::NoWinner {
+            owner: owner.into(),
+            collection: prize.collection,
+            token_id: prize.token.clone(),
+            prize_index,
+        }),
+    )
+}
+
+/// Closes a raffle nobody entered by handing every prize back to the owner.
+fn finish_empty_draw() {
+    let prizes: Vec<Prize> = utils::read_from(PRIZES);
+
+    for prize_index in 0..prizes.len() as u64 {
+        return_prize_to_owner(prize_index);
+    }
+
+    set_status(Status::NoWinner);
+}
+
+fn finish_draw(hash_bytes: [u8; 32]) {
+    let partipiciant_count: u64 = utils::read_from(PARTIPICANT_COUNT);
+
+    if partipiciant_count == 0 {
+        runtime::revert(Error::NoPartipiciants);
+    }
+
+    // 50/50 pots are carved out of the ticket sales before any proceeds
+    let sales: U512 = payment::balance();
+    let mut prizes: Vec<Prize> = utils::read_from(PRIZES);
+    let pot: U512 = prizes
+        .iter_mut()
+        .map(|prize| prize.resolve_pot(sales))
+        .fold(U512::zero(), U512::add);
+
+    if !pot.is_zero() {
+        let raffle_purse = utils::get_uref(PURSE);
+        let prize_purse = utils::get_uref(PRIZE_PURSE);
+
+        system::transfer_from_purse_to_purse(raffle_purse, prize_purse, pot, None)
+            .unwrap_or_revert();
+    }
+
+    runtime::put_key(PRIZES, storage::new_uref(prizes.clone()).into());
+
+    // one distinct ticket per prize, as far as the tickets go
+    let winner_count = partipiciant_count.min(prizes.len() as u64);
+    let mut winners: Vec<u64> = Vec::new();
+
+    for prize_index in 0..winner_count {
+        let prize_hash = sha3_256(&[&hash_bytes, &prize_index.to_be_bytes()]);
+        winners.push(pick_ticket(&prize_hash, partipiciant_count, &winners));
+    }
+
+    let now: u64 = runtime::get_blocktime().into();
+    let claim_period: u64 = utils::read_from(CLAIM_PERIOD);
+    let claim_deadlines: Vec<u64> = vec![now.add(claim_period); winners.len()];
+
+    runtime::put_key(WINNER, storage::new_uref(winners.clone()).into());
+    runtime::put_key(SEED, storage::new_uref(hash_bytes).into());
+    runtime::put_key(FORFEITED, storage::new_uref(Vec::<u64>::new()).into());
+    runtime::put_key(CLAIM_DEADLINE, storage::new_uref(claim_deadlines).into());
+    set_status(Status::Drawn);
+
+    for prize_index in winner_count..prizes.len() as u64 {
+        return_prize_to_owner(prize_index);
+    }
+
+    // proceeds stay in the purse until each recipient calls `withdraw_proceeds`
+    let balance: U512 = sales - pot;
+
+    let fee_bps: u64 = utils::read_from(FEE_BPS);
+    let fee_recipient: AccountHash = utils::read_from(FEE_RECIPIENT);
+    let fee_amount: U512 = balance * U512::from(fee_bps) / U512::from(MAX_BPS);
+    let owner_amount: U512 = balance - fee_amount;
+
+    accrue_proceeds(fee_recipient, fee_amount);
+
+    // the last recipient also receives the rounding dust
+    let revenue_split: Vec<(AccountHash, u64)> = utils::read_from(REVENUE_SPLIT);
+    let mut remaining: U512 = owner_amount;
+
+    for (index, (recipient, share_bps)) in revenue_split.iter().enumerate() {
+        let amount: U512 = if index + 1 == revenue_split.len() {
+            remaining
+        } else {
+            owner_amount * U512::from(*share_bps) / U512::from(MAX_BPS)
+        };
+        remaining -= amount;
+
+        accrue_proceeds(*recipient, amount);
+    }
+
+    emit(
+        &(RaffleEvent::Draw {
+            winners,
+            owner_amount,
+            fee_amount,
+        }),
+    )
+}
+
+/// Prices `quantity` tickets at the cheapest mix of bundles and single tickets.
+fn quote_tickets(quantity: u64) -> U512 {
+    let price: U512 = utils::read_from(PRICE);
+    let bundles: Vec<(u64, U512)> = utils::read_from(BUNDLES);
+
+    // the bundle with the lowest price per ticket, every bundle beats single tickets
+    let best_bundle = bundles
+        .iter()
+        .min_by(|(a_quantity, a_price), (b_quantity, b_price)| {
+            (*a_price * U512::from(*b_quantity)).cmp(&(*b_price * U512::from(*a_quantity)))
+        });
+
+    let (best_quantity, best_price) = match best_bundle {
+        Some(bundle) => *bundle,
+        None => {
+            return price
+                .checked_mul(U512::from(quantity))
+                .unwrap_or_revert_with(Error::PaymentError)
+        }
+    };
+
+    // any `best_quantity` other bundles or single tickets include some whose tickets add
+    // up to a multiple of `best_quantity`, which best bundles sell for no more, so the
+    // cheapest purchase holds fewer than `best_quantity` of them and the rest is best bundles
+    let largest_quantity: u64 = bundles[0].0;
+    let limit: u64 = (best_quantity - 1).saturating_mul(largest_quantity);
+    let excess: u64 = quantity.saturating_sub(limit);
+    let best_count: u64 = excess / best_quantity + u64::from(excess % best_quantity != 0);
+    let rest: usize = (quantity - best_count * best_quantity) as usize;
+
+    // cheapest[n] is the lowest price of exactly `n` tickets
+    let mut cheapest: Vec<U512> = Vec::with_capacity(rest + 1);
+    cheapest.push(U512::zero());
+
+    for tickets in 1..=rest {
+        let mut cost: U512 = cheapest[tickets - 1]
+            .checked_add(price)
+            .unwrap_or_revert_with(Error::PaymentError);
+
+        for (bundle_quantity, bundle_price) in bundles.iter() {
+            let bundle_quantity = *bundle_quantity as usize;
+
+            if bundle_quantity <= tickets {
+                let bundle_cost = cheapest[tickets - bundle_quantity]
+                    .checked_add(*bundle_price)
+                    .unwrap_or_revert_with(Error::PaymentError);
+
+                if bundle_cost < cost {
+                    cost = bundle_cost;
+                }
+            }
+        }
+
+        cheapest.push(cost);
+    }
+
+    best_price
+        .checked_mul(U512::from(best_count))
+        .and_then(|best_cost| best_cost.checked_add(cheapest[rest]))
+        .unwrap_or_revert_with(Error::PaymentError)
+}
+
+fn accrue_proceeds(account: AccountHash, amount: U512) {
+    if amount.is_zero() {
+        return;
+    }
+
+    let proceeds_dict = utils::get_uref(PROCEEDS_DICT);
+    let proceeds: U512 = storage::dictionary_get(proceeds_dict, &account.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_default();
+
+    storage::dictionary_put(proceeds_dict, &account.to_string(), proceeds.add(amount));
+}
+
+/// Maps `hash_bytes` onto one of the `partipiciant_count` tickets, skipping `excluded`.
+fn pick_ticket(hash_bytes: &[u8; 32], partipiciant_count: u64, excluded: &[u64]) -> u64 {
+    let mut excluded = excluded.to_vec();
+    excluded.sort_unstable();
+    excluded.dedup();
+
+    let remaining = partipiciant_count
+        .checked_sub(excluded.len() as u64)
+        .filter(|remaining| *remaining > 0)
+        .unwrap_or_revert_with(Error::NoPartipiciants);
+    let mut ticket = bytes_to_u64(hash_bytes) % remaining;
+
+    for index in excluded {
+        if index <= ticket {
+            ticket += 1;
+        }
+    }
+
+    ticket
+}
+
+fn sha3_256(inputs: &[&[u8]]) -> [u8; 32] {
+    let mut sha3 = Sha3::v256();
+
+    for input in inputs {
+        sha3.update(input);
+    }
+
+    let mut hash_bytes = [0u8; 32]; // SHA-3-256 for 32 byte
+    sha3.finalize(&mut hash_bytes);
+    hash_bytes
+}
+
 fn bytes_to_u64(bytes: &[u8]) -> u64 {
     let mut result: u64 = 0;
     for i in 0..8 {
@@ -391,26 +1449,3 @@ pub fn check_admin_account() {
         runtime::revert(Error::AdminError);
     }
 }
-
-pub fn get_approved(contract_hash: ContractHash, owner: Address, token_id: u64) -> Option<Key> {
-    runtime::call_contract::<Option<Key>>(
-        contract_hash,
-        "get_approved",
-        runtime_args! {
-          "owner" => owner,
-          "token_id" => token_id
-        },
-    )
-}
-
-pub fn transfer(contract_hash: ContractHash, sender: Key, recipient: Key, token_id: u64) -> () {
-    runtime::call_contract::<()>(
-        contract_hash,
-        "transfer",
-        runtime_args! {
-            "token_id" => token_id,
-            "source_key" => sender,
-            "target_key" => recipient,
-        },
-    )
-}
@@ -5,7 +5,9 @@ use crate::error::Error;
 use casper_contract::{
     contract_api::runtime, contract_api::storage, unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_types::{bytesrepr::FromBytes, system::CallStackElement, CLTyped, URef, U512};
+use casper_types::{
+    bytesrepr::FromBytes, system::CallStackElement, CLTyped, ContractHash, URef, U512,
+};
 use core::convert::TryInto;
 
 fn current_timestamp() -> U512 {
@@ -30,6 +32,30 @@ pub fn get_current_address() -> Address {
     }
 }
 
+/// Returns the package of the currently running contract, which is the identity
+/// token contracts see it under when it calls them.
+pub fn get_current_package_address() -> Address {
+    match runtime::get_call_stack().into_iter().rev().next() {
+        Some(CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        }) => Address::from(contract_package_hash),
+        _ => runtime::revert(Error::FatalError),
+    }
+}
+
+pub fn get_current_contract_hash() -> ContractHash {
+    match runtime::get_call_stack().into_iter().rev().next() {
+        Some(CallStackElement::StoredContract { contract_hash, .. }) => contract_hash,
+        _ => runtime::revert(Error::FatalError),
+    }
+}
+
+/// Returns the call stack element that invoked the currently running contract.
+pub fn get_immediate_caller() -> Option<CallStackElement> {
+    runtime::get_call_stack().into_iter().rev().nth(1)
+}
+
 pub fn get_key<T: FromBytes + CLTyped>(name: &str) -> T {
     let key = runtime::get_key(name)
         .unwrap_or_revert_with(Error::FatalError)
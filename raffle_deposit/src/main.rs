@@ -13,33 +13,45 @@ use casper_types::{
 };
 
 const RAFFLE_CONTRACT_HASH: &str = "raffle_contract_hash";
-// const ENTRY_POINT_GET_PRICE: &str = "get_price";
-const ENTRY_POINT_GET_PURSE: &str = "get_purse";
 const ENTRY_POINT_BUY_TICKET: &str = "buy_ticket";
 
 #[no_mangle]
 pub extern "C" fn call() {
     let raffle_contract_hash: ContractHash = runtime::get_named_arg(RAFFLE_CONTRACT_HASH);
     let amount: U512 = runtime::get_named_arg("amount");
-
-    //  let amount: U512 = runtime::call_contract(raffle_contract_hash,ENTRY_POINT_GET_PRICE,runtime_args! {});
-    let deposit_purse: URef = runtime::call_contract(
-        raffle_contract_hash,
-        ENTRY_POINT_GET_PURSE,
-        runtime_args! {},
-    );
+    let quantity: u64 = runtime::get_named_arg("quantity");
+    let commitment: Option<[u8; 32]> = runtime::get_named_arg("commitment");
 
     let caller: AccountHash = runtime::get_caller().into();
-    // Transfer from the caller's main purse to the new purse that was just created.
-    // Note that transfer is done safely by the host logic.
-    system::transfer_from_purse_to_purse(account::get_main_purse(), deposit_purse, amount, None)
+
+    // token raffles take no motes, so they get no purse
+    let deposit_purse: Option<URef> = if amount.is_zero() {
+        None
+    } else {
+        let deposit_purse: URef = system::create_purse();
+
+        // Transfer from the caller's main purse to the new purse that was just created.
+        // Note that transfer is done safely by the host logic.
+        system::transfer_from_purse_to_purse(
+            account::get_main_purse(),
+            deposit_purse,
+            amount,
+            None,
+        )
         .unwrap_or_revert();
 
+        Some(deposit_purse)
+    };
+
+    // the raffle contract pulls the ticket price out of this purse itself
     runtime::call_contract::<()>(
         raffle_contract_hash,
         ENTRY_POINT_BUY_TICKET,
         runtime_args! {
             "partipiciant" => Key::Account(caller),
+            "quantity" => quantity,
+            "purse" => deposit_purse,
+            "commitment" => commitment,
         },
     );
 }
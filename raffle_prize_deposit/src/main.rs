@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{
+    contract_api::{account, runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, ContractHash, RuntimeArgs, URef, U512};
+
+const RAFFLE_CONTRACT_HASH: &str = "raffle_contract_hash";
+const ENTRY_POINT_DEPOSIT: &str = "deposit";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let raffle_contract_hash: ContractHash = runtime::get_named_arg(RAFFLE_CONTRACT_HASH);
+    let amount: U512 = runtime::get_named_arg("amount");
+
+    let prize_purse: URef = system::create_purse();
+
+    // Transfer the CSPR prizes from the owner's main purse to the new purse that was just created.
+    system::transfer_from_purse_to_purse(account::get_main_purse(), prize_purse, amount, None)
+        .unwrap_or_revert();
+
+    // the raffle contract pulls the prizes out of this purse itself
+    runtime::call_contract::<()>(
+        raffle_contract_hash,
+        ENTRY_POINT_DEPOSIT,
+        runtime_args! {
+            "purse" => Some(prize_purse),
+        },
+    );
+}
@@ -0,0 +1,91 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::ToString, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, ApiError, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Key, Parameter,
+};
+
+// A CEP-47 style collection for the engine tests, which only tracks who holds each token.
+
+const OWNERS_DICT: &str = "owners";
+const CONTRACT_HASH_KEY: &str = "mock_collection_contract_hash";
+const PACKAGE_HASH_KEY: &str = "mock_collection_package_hash";
+
+/// Every token counts as approved.
+#[no_mangle]
+pub extern "C" fn get_approved() {
+    let owner: Key = runtime::get_named_arg("owner");
+
+    runtime::ret(CLValue::from_t(Some(owner)).unwrap_or_revert());
+}
+
+/// Moves a token, which only its current holder can do once it has one.
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let token_id: u64 = runtime::get_named_arg("token_id");
+    let source_key: Key = runtime::get_named_arg("source_key");
+    let target_key: Key = runtime::get_named_arg("target_key");
+
+    let owners = runtime::get_key(OWNERS_DICT)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    let owner: Option<Key> =
+        storage::dictionary_get(owners, &token_id.to_string()).unwrap_or_revert();
+
+    if owner.is_some() && owner != Some(source_key) {
+        runtime::revert(ApiError::PermissionDenied);
+    }
+
+    storage::dictionary_put(owners, &token_id.to_string(), target_key);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_approved",
+        vec![
+            Parameter::new("owner", CLType::Key),
+            Parameter::new("token_id", CLType::U64),
+        ],
+        Option::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer",
+        vec![
+            Parameter::new("token_id", CLType::U64),
+            Parameter::new("source_key", CLType::Key),
+            Parameter::new("target_key", CLType::Key),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let owners = storage::new_dictionary(OWNERS_DICT).unwrap_or_revert();
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(OWNERS_DICT.to_string(), owners.into());
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(PACKAGE_HASH_KEY.to_string()),
+        None,
+    );
+
+    runtime::put_key(CONTRACT_HASH_KEY, contract_hash.into());
+}
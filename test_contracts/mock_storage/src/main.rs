@@ -0,0 +1,82 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::ToString, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash, bytesrepr::FromBytes, contracts::NamedKeys, CLType, CLTyped, CLValue,
+    EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
+};
+
+// The storage contract raffles register with, set up with a fixed fee for the engine tests.
+
+const FEE_BPS: &str = "fee_bps";
+const FEE_RECIPIENT: &str = "fee_recipient";
+const CONTRACT_HASH_KEY: &str = "mock_storage_contract_hash";
+const PACKAGE_HASH_KEY: &str = "mock_storage_package_hash";
+
+/// Accepts every raffle without recording it.
+#[no_mangle]
+pub extern "C" fn insert() {}
+
+#[no_mangle]
+pub extern "C" fn get_fee() {
+    let fee_bps: u64 = read(FEE_BPS);
+    let fee_recipient: AccountHash = read(FEE_RECIPIENT);
+
+    runtime::ret(CLValue::from_t((fee_bps, fee_recipient)).unwrap_or_revert());
+}
+
+fn read<T: CLTyped + FromBytes>(name: &str) -> T {
+    let uref = runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+
+    storage::read(uref).unwrap_or_revert().unwrap_or_revert()
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let fee_bps: u64 = runtime::get_named_arg(FEE_BPS);
+    let fee_recipient: AccountHash = runtime::get_named_arg(FEE_RECIPIENT);
+
+    let mut entry_points = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "insert",
+        vec![Parameter::new("data", CLType::String)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_fee",
+        vec![],
+        <(u64, AccountHash)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(FEE_BPS.to_string(), storage::new_uref(fee_bps).into());
+    named_keys.insert(
+        FEE_RECIPIENT.to_string(),
+        storage::new_uref(fee_recipient).into(),
+    );
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(PACKAGE_HASH_KEY.to_string()),
+        None,
+    );
+
+    runtime::put_key(CONTRACT_HASH_KEY, contract_hash.into());
+}
@@ -0,0 +1,494 @@
+#[cfg(test)]
+mod tests {
+    use casper_engine_test_support::{
+        ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+        DEFAULT_RUN_GENESIS_REQUEST,
+    };
+    use casper_execution_engine::core::{
+        engine_state::Error as EngineStateError, execution::Error as ExecError,
+    };
+    use casper_types::{
+        account::AccountHash, bytesrepr::FromBytes, runtime_args, ApiError, CLTyped, ContractHash,
+        Key, RuntimeArgs, URef, U512,
+    };
+    use tiny_keccak::{Hasher, Sha3};
+
+    const RAFFLE_WASM: &str = "raffle.wasm";
+    const BUY_TICKET_WASM: &str = "raffle_deposit.wasm";
+    const MOCK_COLLECTION_WASM: &str = "mock_collection.wasm";
+    const MOCK_STORAGE_WASM: &str = "mock_storage.wasm";
+
+    const RAFFLE_NAME: &str = "test_raffle";
+    const INSTALL_TIME: u64 = 1_000;
+    const START_DATE: u64 = 1_000;
+    const SALE_TIME: u64 = 1_500;
+    const END_DATE: u64 = 2_000;
+    const DRAW_TIME: u64 = 3_000;
+    const DRAW_PERIOD: u64 = 2_000;
+    const PAST_DRAW_DEADLINE: u64 = END_DATE + DRAW_PERIOD + 1;
+    const SECRET: [u8; 32] = [7u8; 32];
+
+    const CLAIM_PERIOD: u64 = 100;
+    const FORFEIT_TIME: u64 = DRAW_TIME + CLAIM_PERIOD + 1;
+
+    const STATUS_CLOSED: u8 = 3;
+    const STATUS_DRAWN: u8 = 4;
+    const STATUS_CLAIMED: u8 = 5;
+    const STATUS_CANCELLED: u8 = 6;
+
+    const ERROR_TIME: u16 = 2;
+    const ERROR_NO_DRAW: u16 = 6;
+    const ERROR_PAYMENT: u16 = 8;
+    const ERROR_MAX_TICKETS: u16 = 16;
+    const ERROR_WALLET_LIMIT: u16 = 17;
+    const ERROR_ALREADY_CLAIMED: u16 = 21;
+
+    fn price() -> U512 {
+        cspr(10)
+    }
+
+    fn cspr(amount: u64) -> U512 {
+        U512::from(amount) * U512::from(1_000_000_000u64)
+    }
+
+    /// Bundles where six tickets are cheapest as two bundles of three, not as a bundle
+    /// of four and two single tickets.
+    fn bundles() -> Vec<(u64, U512)> {
+        vec![(3, cspr(24)), (4, cspr(35))]
+    }
+
+    struct RaffleConfig {
+        prize_count: u64,
+        bundles: Vec<(u64, U512)>,
+        claim_period: u64,
+        max_tickets: u64,
+        max_tickets_per_wallet: u64,
+    }
+
+    impl Default for RaffleConfig {
+        fn default() -> Self {
+            RaffleConfig {
+                prize_count: 1,
+                bundles: Vec::new(),
+                claim_period: 0,
+                max_tickets: 0,
+                max_tickets_per_wallet: 0,
+            }
+        }
+    }
+
+    struct TestContext {
+        builder: InMemoryWasmTestBuilder,
+        raffle_hash: ContractHash,
+    }
+
+    impl TestContext {
+        /// Installs the mock storage and collection, then a raffle whose prizes are
+        /// tokens `1..=prize_count` of the collection, and deposits them.
+        fn new(config: RaffleConfig) -> Self {
+            let mut builder = InMemoryWasmTestBuilder::default();
+            builder.run_genesis(&*DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+            let storage_request = ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                MOCK_STORAGE_WASM,
+                runtime_args! {
+                    "fee_bps" => 0u64,
+                    "fee_recipient" => *DEFAULT_ACCOUNT_ADDR,
+                },
+            )
+            .build();
+            builder.exec(storage_request).expect_success().commit();
+
+            let collection_request = ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                MOCK_COLLECTION_WASM,
+                runtime_args! {},
+            )
+            .build();
+            builder.exec(collection_request).expect_success().commit();
+
+            let storage_hash = account_contract_hash(&builder, "mock_storage_contract_hash");
+            let collection_hash = account_contract_hash(&builder, "mock_collection_contract_hash");
+
+            let prizes: Vec<(Key, u8, String)> = (1..=config.prize_count)
+                .map(|token_id| (Key::from(collection_hash), 0u8, token_id.to_string()))
+                .collect();
+
+            let raffle_request = ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                RAFFLE_WASM,
+                runtime_args! {
+                    "name" => RAFFLE_NAME.to_string(),
+                    "start_date" => START_DATE,
+                    "end_date" => END_DATE,
+                    "price" => price(),
+                    "bundles" => config.bundles,
+                    "prizes" => prizes,
+                    "payment_token" => Option::<ContractHash>::None,
+                    "storage_key" => storage_hash,
+                    "secret_hash" => sha3_256(&SECRET),
+                    "reveal_deposit" => U512::zero(),
+                    "reveal_duration" => 0u64,
+                    "randomness_provider" => Option::<ContractHash>::None,
+                    "min_tickets" => 0u64,
+                    "max_tickets" => config.max_tickets,
+                    "max_tickets_per_wallet" => config.max_tickets_per_wallet,
+                    "revenue_split" => Vec::<(AccountHash, u64)>::new(),
+                    "claim_period" => config.claim_period,
+                    "draw_period" => DRAW_PERIOD,
+                },
+            )
+            .with_block_time(INSTALL_TIME)
+            .build();
+            builder.exec(raffle_request).expect_success().commit();
+
+            let raffle_hash = account_contract_hash(
+                &builder,
+                &format!("raffles_contract_hash_{}_{}", RAFFLE_NAME, INSTALL_TIME),
+            );
+
+            let mut context = TestContext {
+                builder,
+                raffle_hash,
+            };
+
+            context.call(
+                "deposit",
+                runtime_args! {
+                    "purse" => Option::<URef>::None,
+                },
+                INSTALL_TIME,
+            );
+            context.builder.expect_success();
+
+            context
+        }
+
+        /// Calls `entry_point` of the raffle as the default account.
+        fn call(&mut self, entry_point: &str, args: RuntimeArgs, block_time: u64) {
+            let request = ExecuteRequestBuilder::contract_call_by_hash(
+                *DEFAULT_ACCOUNT_ADDR,
+                self.raffle_hash,
+                entry_point,
+                args,
+            )
+            .with_block_time(block_time)
+            .build();
+
+            self.builder.exec(request).commit();
+        }
+
+        /// Buys `quantity` tickets through the buying session, paying exactly `amount`.
+        fn buy_tickets(&mut self, quantity: u64, amount: U512, block_time: u64) {
+            let request = ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                BUY_TICKET_WASM,
+                runtime_args! {
+                    "raffle_contract_hash" => self.raffle_hash,
+                    "amount" => amount,
+                    "quantity" => quantity,
+                    "commitment" => Option::<[u8; 32]>::None,
+                },
+            )
+            .with_block_time(block_time)
+            .build();
+
+            self.builder.exec(request).commit();
+        }
+
+        fn draw(&mut self) {
+            self.call(
+                "draw",
+                runtime_args! {
+                    "secret" => SECRET,
+                },
+                DRAW_TIME,
+            );
+            self.builder.expect_success();
+        }
+
+        fn query<T: CLTyped + FromBytes>(&self, name: &str) -> T {
+            self.builder
+                .query(None, Key::from(self.raffle_hash), &[name.to_string()])
+                .expect("named key should exist")
+                .as_cl_value()
+                .expect("should be a CLValue")
+                .clone()
+                .into_t()
+                .expect("should have the expected type")
+        }
+
+        fn assert_user_error(&self, code: u16) {
+            let error = self
+                .builder
+                .get_error()
+                .expect("the call should have failed");
+
+            assert!(
+                matches!(
+                    error,
+                    EngineStateError::Exec(ExecError::Revert(ApiError::User(user_code)))
+                        if user_code == code
+                ),
+                "expected user error {}, got {:?}",
+                code,
+                error
+            );
+        }
+    }
+
+    /// Runs a drawn raffle with three tickets and two prizes whose claim period has passed.
+    fn drawn_with_two_prizes_and_three_tickets() -> TestContext {
+        let mut context = TestContext::new(RaffleConfig {
+            prize_count: 2,
+            claim_period: CLAIM_PERIOD,
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(3, price() * 3, SALE_TIME);
+        context.builder.expect_success();
+        context.draw();
+
+        context
+    }
+
+    fn redraw(context: &mut TestContext, prize_index: u64, reclaim: bool, block_time: u64) {
+        context.call(
+            "redraw",
+            runtime_args! {
+                "prize_index" => prize_index,
+                "reclaim" => reclaim,
+            },
+            block_time,
+        );
+        context.builder.expect_success();
+    }
+
+    fn account_contract_hash(builder: &InMemoryWasmTestBuilder, name: &str) -> ContractHash {
+        let key: Key = *builder
+            .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+            .named_keys()
+            .get(name)
+            .expect("account should have the contract hash");
+
+        key.into_hash()
+            .map(ContractHash::new)
+            .expect("should be a contract hash")
+    }
+
+    fn sha3_256(input: &[u8]) -> [u8; 32] {
+        let mut sha3 = Sha3::v256();
+        let mut output = [0u8; 32];
+
+        sha3.update(input);
+        sha3.finalize(&mut output);
+
+        output
+    }
+
+    #[test]
+    fn should_buy_tickets() {
+        let mut context = TestContext::new(RaffleConfig::default());
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+
+        // the second purchase reads the wallet's ticket count back
+        context.buy_tickets(2, price() * 2, SALE_TIME);
+        context.builder.expect_success();
+
+        assert_eq!(context.query::<u64>("partipiciant_count"), 3);
+        assert_eq!(context.query::<u64>("purchase_count"), 2);
+    }
+
+    #[test]
+    fn should_not_sell_past_max_tickets() {
+        let mut context = TestContext::new(RaffleConfig {
+            max_tickets: 3,
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(2, price() * 2, SALE_TIME);
+        context.builder.expect_success();
+
+        context.buy_tickets(2, price() * 2, SALE_TIME);
+        context.assert_user_error(ERROR_MAX_TICKETS);
+    }
+
+    #[test]
+    fn should_not_sell_past_the_wallet_limit() {
+        let mut context = TestContext::new(RaffleConfig {
+            max_tickets_per_wallet: 2,
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+
+        // the limit is checked against what the wallet already holds
+        context.buy_tickets(2, price() * 2, SALE_TIME);
+        context.assert_user_error(ERROR_WALLET_LIMIT);
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+    }
+
+    #[test]
+    fn should_close_once_sold_out() {
+        let mut context = TestContext::new(RaffleConfig {
+            max_tickets: 2,
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(2, price() * 2, SALE_TIME);
+        context.builder.expect_success();
+
+        assert_eq!(context.query::<u8>("status"), STATUS_CLOSED);
+        assert_eq!(context.query::<u64>("end_date"), SALE_TIME);
+
+        // no need to wait for the original end date
+        context.call(
+            "draw",
+            runtime_args! {
+                "secret" => SECRET,
+            },
+            SALE_TIME + 1,
+        );
+        context.builder.expect_success();
+
+        assert_eq!(context.query::<u8>("status"), STATUS_DRAWN);
+    }
+
+    #[test]
+    fn should_not_claim_before_draw() {
+        let mut context = TestContext::new(RaffleConfig::default());
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+
+        context.call(
+            "claim",
+            runtime_args! {
+                "prize_index" => 0u64,
+            },
+            SALE_TIME,
+        );
+        context.assert_user_error(ERROR_NO_DRAW);
+    }
+
+    #[test]
+    fn should_not_claim_twice() {
+        let mut context = TestContext::new(RaffleConfig::default());
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+        context.draw();
+
+        context.call(
+            "claim",
+            runtime_args! {
+                "prize_index" => 0u64,
+            },
+            DRAW_TIME,
+        );
+        context.builder.expect_success();
+
+        context.call(
+            "claim",
+            runtime_args! {
+                "prize_index" => 0u64,
+            },
+            DRAW_TIME,
+        );
+        context.assert_user_error(ERROR_ALREADY_CLAIMED);
+    }
+
+    #[test]
+    fn should_redraw_after_a_reclaimed_forfeit() {
+        let mut context = drawn_with_two_prizes_and_three_tickets();
+        let winners: Vec<u64> = context.query("winner");
+
+        // the forfeited ticket stays the winner of the reclaimed prize
+        redraw(&mut context, 0, true, FORFEIT_TIME);
+        redraw(&mut context, 1, false, FORFEIT_TIME);
+
+        let redrawn: Vec<u64> = context.query("winner");
+        assert_eq!(redrawn[1], 3 - winners[0] - winners[1]);
+        assert_eq!(context.query::<Vec<bool>>("claimed"), vec![true, false]);
+        assert_eq!(context.query::<u8>("status"), STATUS_DRAWN);
+    }
+
+    #[test]
+    fn should_return_the_prize_once_every_ticket_forfeited() {
+        let mut context = drawn_with_two_prizes_and_three_tickets();
+
+        redraw(&mut context, 0, true, FORFEIT_TIME);
+        redraw(&mut context, 1, false, FORFEIT_TIME);
+        redraw(&mut context, 1, false, FORFEIT_TIME + CLAIM_PERIOD + 1);
+
+        assert_eq!(context.query::<Vec<u64>>("forfeited").len(), 3);
+        assert_eq!(context.query::<Vec<bool>>("claimed"), vec![true, true]);
+        assert_eq!(context.query::<u8>("status"), STATUS_CLAIMED);
+    }
+
+    #[test]
+    fn should_charge_the_cheapest_mix_of_bundles() {
+        let mut context = TestContext::new(RaffleConfig {
+            bundles: bundles(),
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(6, cspr(48), SALE_TIME);
+        context.builder.expect_success();
+
+        // two bundles of three and a single ticket beat a bundle of four and one of three
+        context.buy_tickets(7, cspr(58), SALE_TIME);
+        context.builder.expect_success();
+
+        context.buy_tickets(2, cspr(20), SALE_TIME);
+        context.builder.expect_success();
+
+        assert_eq!(context.query::<u64>("partipiciant_count"), 15);
+    }
+
+    #[test]
+    fn should_reject_overpaying_for_six_tickets() {
+        let mut context = TestContext::new(RaffleConfig {
+            bundles: bundles(),
+            ..RaffleConfig::default()
+        });
+
+        context.buy_tickets(6, cspr(55), SALE_TIME);
+        context.assert_user_error(ERROR_PAYMENT);
+    }
+
+    #[test]
+    fn should_refund_once_the_draw_deadline_passed() {
+        let mut context = TestContext::new(RaffleConfig::default());
+
+        context.buy_tickets(1, price(), SALE_TIME);
+        context.builder.expect_success();
+
+        context.call(
+            "draw",
+            runtime_args! {
+                "secret" => SECRET,
+            },
+            PAST_DRAW_DEADLINE,
+        );
+        context.assert_user_error(ERROR_TIME);
+
+        context.call("refund", runtime_args! {}, PAST_DRAW_DEADLINE);
+        context.builder.expect_success();
+
+        context.call("cancel", runtime_args! {}, PAST_DRAW_DEADLINE);
+        context.builder.expect_success();
+
+        assert_eq!(context.query::<u8>("status"), STATUS_CANCELLED);
+    }
+}
+
+fn main() {
+    panic!("Execute \"cargo test\" to test the contract, not \"cargo run\".");
+}